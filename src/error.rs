@@ -26,6 +26,8 @@ pub(crate) enum ParseError {
     InvalidArguments,
     #[error("Invalid argument for clang-format version.")]
     InvalidVersionArgument,
+    #[error("Invalid rule file. {0}")]
+    InvalidRules(String),
     #[error(transparent)]
     Other(#[from] Box<dyn std::error::Error>),
 }
\ No newline at end of file
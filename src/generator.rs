@@ -1,6 +1,17 @@
-static REF_CONTENT: &str = r#"
+pub(crate) static REF_CONTENT: &str = r#"
 ///////////////////////////////////// MAX WIDTH ///////////////////////////////////////////////////////////
 
+#include "reference.h"
+
+#include "project/first_header.h"
+#include "project/second_header.h"
+
+#include <third_party/library.h>
+
+#include <map>
+#include <string>
+#include <vector>
+
 namespace lib
 {
 
@@ -19,6 +30,7 @@ class ReferenceClass
 {
 public:
     ReferenceClass(int* value, float& ref)
+        : pointer_member(value), reference_member(ref), counter_member(0), fraction_member(0.5f)
     {
         if (value) 
         {
@@ -29,11 +41,337 @@ public:
             int a = 6;
         }
     }
+    explicit ReferenceClass(int count) : reference_member(fraction_member), counter_member(count) {}
 private:
     int values[5];
+    int* pointer_member;
+    float& reference_member;
+    int counter_member;
+    float fraction_member;
+};
+
+class ShortFunctions
+{
+public:
+    int get_value() const { return value; }
+private:
+    int value;
+};
+
+void empty_function() {}
+
+int short_function() { return 0; }
+
+void short_statements(int count)
+{
+    if (count) return;
+    if (count == 1) count = 0;
+    else count = 1;
+    while (count) count--;
+    if (count > 1) {}
+    while (count > 2) {}
+    auto short_lambda = [](int x) { return x; };
+}
+
+enum SHORT_ENUM { SHORT_A, SHORT_B };
+
+void long_function_declaration(int first_parameter, int second_parameter, int third_parameter,
+                               int fourth_parameter, int fifth_parameter, int sixth_parameter);
+
+void function_with_a_long_name_and_parameters_that_fit_on_the_next_line(
+    int first_parameter, int second_parameter, int third_parameter);
+
+std::map<std::string, std::vector<int>>
+wrapped_function_name_after_a_long_return_type(int first_parameter, int second_parameter);
+
+void function_calls(int first_argument, int second_argument, int third_argument)
+{
+    long_function_declaration(first_argument, second_argument, third_argument, first_argument,
+                              second_argument, third_argument);
+    function_with_a_long_name_and_parameters_that_fit_on_the_next_line(
+        first_argument, second_argument, third_argument);
+}
+
+class ReturnTypes
+{
+public:
+    std::vector<int> member_declaration(int parameter);
+    std::vector<int> member_definition(int parameter)
+    {
+        std::vector<int> values;
+        return values;
+    }
+};
+
+std::vector<int> top_level_declaration(int parameter);
+
+std::vector<int> top_level_definition(int parameter)
+{
+    std::vector<int> values;
+    return values;
+}
+
+class SingleBase : public test_data
+{
+};
+
+class DerivedClassWithSeveralBases
+    : public ReferenceClass, public ShortFunctions, public ReturnTypes, private SingleBase
+{
+};
+
+template <typename T>
+class TemplateClass
+{
+public:
+    std::vector<T> values;
+};
+
+template <typename T>
+T short_template_function(T value);
+
+template <typename T>
+T template_function(T value)
+{
+    return value;
+}
+
+template <typename T>
+concept Incrementable = requires(T value) {
+    value++;
+    ++value;
+};
+
+template <typename T>
+    requires Incrementable<T>
+T constrained_function(T value)
+{
+    return value;
+}
+
+void lambdas(std::vector<int>& values_with_a_long_name)
+{
+    int counter = 0;
+    auto empty_lambda = []() {};
+    call_with_lambda([](int x) { return x; });
+    std::for_each(values_with_a_long_name.begin(), values_with_a_long_name.end(),
+                  [&counter, &values_with_a_long_name](int value) {
+                      counter += value;
+                  });
+}
+
+#if defined(FEATURE_ENABLED)
+#ifdef NESTED_FEATURE
+#define NESTED_VALUE 1
+#endif
+#endif
+
+#define MULTI_LINE_MACRO(a, b) \
+    do                         \
+    {                          \
+        a = b;                 \
+    } while (0)
+
+#define STRINGIZE_TEXT(text) #text
+
+BEGIN_TABLE(reference_table)
+    TABLE_ENTRY(first)
+    TABLE_ENTRY(second)
+END_TABLE()
+
+void consecutive_alignment()
+{
+    int assign_short       = 1;
+    int assign_longer_name = 2;
+    assign_short          += 3;
+
+    int assign_after_empty_line = 4;
+    // comment between assignments
+    int assign_after_comment = 5;
+
+    int    declared_int;
+    double declared_double;
+
+    std::string declared_after_empty_line;
+    // comment between declarations
+    char declared_after_comment;
+}
+
+struct BitFields
+{
+    unsigned int bit_short       : 1;
+    unsigned int bit_longer_name : 2;
+
+    unsigned int bit_after_empty_line : 3;
+    // comment between bit fields
+    unsigned int bit_after_comment : 4;
+};
+
+#define MACRO_SHORT       1
+#define MACRO_LONGER_NAME 2
+
+#define MACRO_AFTER_EMPTY_LINE 3
+// comment between macros
+#define MACRO_AFTER_COMMENT 4
+
+void trailing_comments()
+{
+    comment_short(1);       // first trailing comment
+    comment_longer_name(2); // second trailing comment
+
+    comment_after_empty_line(3); // third trailing comment
+}
+
+// This comment is longer than the column limit and shows whether long comments are reflowed by clang-format or left as they are.
+
+namespace detail
+{
+int detail_value = 0;
+} // namespace detail
+
+void long_expressions(bool first_condition, bool second_condition, int first_operand, int second_operand)
+{
+    bool long_boolean_expression = first_condition && second_condition && first_condition &&
+                                   second_condition && first_condition;
+
+    int long_arithmetic_expression = first_operand * second_operand + first_operand * second_operand +
+                                     first_operand * second_operand;
+
+    int long_ternary_expression = first_condition
+                                      ? first_operand * second_operand + first_operand * second_operand
+                                      : second_operand;
+
+    int result_of_a_long_assignment_statement =
+        compute_a_value_with_a_rather_long_function_name(first_operand, second_operand, first_operand);
+}
+
+struct SingleBitField
+{
+    unsigned int single_bit : 1;
+};
+
+void spacing(std::vector<int> values, int count)
+{
+    double cast_value = (double)count;
+    bool negated_value = !count;
+    std::vector<int> braced_list{1, 2, 3};
+    void* const* qualified_pointer = nullptr;
+    for (int value : values)
+        count += value;
+    switch (count)
+    {
+    case 1:
+        break;
+    }
+}
+
+class OperatorClass
+{
+public:
+    bool operator==(const OperatorClass& other) const;
+};
+
+template <typename T>
+    requires(sizeof(T) > 1)
+T sized_function(T value);
+
+__attribute__((noreturn)) void no_return_function();
+
+void parentheses(std::vector<int>& values, char* buffer)
+{
+    BOOST_FOREACH (int value, values)
+        call_with_arguments(value);
+    KJ_IF_MAYBE (pointer, values)
+        call_without_arguments();
+    int* placement_value = new (buffer) int(5);
+}
+
+struct TableEntry
+{
+    int id;
+    const char* name;
+    double weight;
+};
+
+TableEntry table_entries[] = {
+    {1, "first", 0.5},
+    {20, "second", 12.25},
+    {300, "third", 7.0},
 };
 
+static constexpr int qualifier_constant = 1;
+static const volatile int qualifier_volatile = 2;
+const int qualifier_west = 3;
+int const qualifier_east = 3;
+inline const std::string qualifier_name = "name";
+
+void qualifier_parameters(const std::string& qualifier_reference, const int* qualifier_pointer);
+
+class AccessModifiers
+{
+public:
+    int public_member;
+    // comment before an access modifier
+protected:
+    int protected_member;
+};
+
+void empty_line_at_start_of_block()
+{
+
+    int value = 0;
+}
+
+int redundant_parentheses(int value)
+{
+    int doubled_value = ((value * 2));
+    return (doubled_value);
+}
+
+int extra_semicolon() { return 1; };
+
+void integer_literals()
+{
+    int short_decimal = 1000;
+    int long_decimal = 1000000;
+    int short_hex = 0xFFFF;
+    int long_hex = 0xFFFFFFFF;
+    int short_binary = 0b1010;
+    int long_binary = 0b10101010;
+}
+
+class EXPORT_API ExportedClass
+{
+    Q_OBJECT
+public:
+    EXPORT_API void exported_function();
+};
+
+void macro_usage(std::vector<int>& values)
+{
+    FOREACH_ITEM (int value, values)
+    {
+        Q_EMIT value_changed(value);
+    }
+    IF_VALID (values)
+    {
+        LOG_STATEMENT("valid")
+    }
+    else
+    {
+        STACK_OF(int) stacked_values;
+    }
+}
+
+[[nodiscard]] int attributed_function();
+
+[[deprecated]]
+int deprecated_function();
+
+const char* long_string_literal = "This string literal is longer than the column limit, it shows whether long literals are split";
+
 } // namespace lib
+
 "#;
 
 pub(crate) fn generate_reference_file(lines: &mut Vec<String>) {
@@ -51,6 +389,6 @@ mod tests {
     fn test_generate_reference() {
         let mut lines = Vec::new();
         generate_reference_file(&mut lines);
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
     }
 }
\ No newline at end of file
@@ -2,6 +2,7 @@ mod clang_format_lib;
 mod error;
 mod generator;
 mod parser;
+mod rules;
 mod write_cfg;
 
 use clang_format_lib::{ClangFormatSettings, Parser};
@@ -9,14 +10,14 @@ use clap::{Arg, Command};
 use error::{ParseError, ParseResult};
 use std::cell::RefCell;
 use std::fs::{self, OpenOptions};
-use std::io::{BufRead, Write};
+use std::io::Write;
 use std::path::Path;
 use std::rc::Rc;
 
 type TextFileContent = Vec<String>;
 
 // Function to return the correct line ending based on the OS
-fn line_ending() -> &'static [u8] {
+fn native_line_ending() -> &'static [u8] {
     #[cfg(windows)]
     {
         b"\r\n"
@@ -28,13 +29,22 @@ fn line_ending() -> &'static [u8] {
     }
 }
 
-fn write_to_file(dst: &Path, lines: &TextFileContent) -> std::result::Result<(), String> {
+// Line ending of the written file as chosen on the command line
+fn line_ending(argument: &str) -> &'static [u8] {
+    match argument {
+        "lf" => b"\n",
+        "crlf" => b"\r\n",
+        _ => native_line_ending(),
+    }
+}
+
+fn write_to_file(dst: &Path, lines: &TextFileContent, ending: &[u8]) -> std::result::Result<(), String> {
     // let mut file = match File::create(dst) {
     //     Ok(file) => file,
     //     Err(_) => return Err(String::from("Could not open file to write.")),
     // };
 
-    let mut file = match OpenOptions::new().write(true).create(true).open(dst) {
+    let mut file = match OpenOptions::new().write(true).create(true).truncate(true).open(dst) {
         Ok(file) => file,
         Err(_) => return Err(String::from("Could not open file to write.")),
     };
@@ -43,18 +53,18 @@ fn write_to_file(dst: &Path, lines: &TextFileContent) -> std::result::Result<(),
         // if let Err(_) = writeln!(file, "{}", line) {
         //     return Err(String::from("Failure writing to the file."));
         // }
-        if let Err(_) = file.write_all(line.as_bytes()) {
+        if file.write_all(line.as_bytes()).is_err() {
             return Err(String::from("Failure writing to the file."));
         }
 
         // Conditionally compile to use the correct line ending
-        if let Err(_) = file.write_all(line_ending()) {
+        if file.write_all(ending).is_err() {
             return Err(String::from("Failure writing to the file."));
         }
         
     }
 
-    if let Err(_) = file.flush() {
+    if file.flush().is_err() {
         return Err(String::from("Failure closing the file."));
     }
 
@@ -65,19 +75,26 @@ fn create_clang_format_file(
     settings: Rc<RefCell<clang_format_lib::ClangFormatSettings>>,
     dst: &Path,
     version: u32,
+    ending: &[u8],
 ) -> Result<(), ParseError> {
     let mut file_content: TextFileContent = vec![];
 
     write_cfg::write_clang_format_file(&settings.borrow(), version, &mut file_content);
 
-    if let Err(err) = write_to_file(dst, &file_content) {
+    if let Err(err) = write_to_file(dst, &file_content, ending) {
         return Err(ParseError::WriteFileError(err));
     }
 
     Ok(())
 }
 
-fn parse_clang_format_settings(src: &Path, dst: &Path, version: u32) -> Result<(), ParseError> {
+fn parse_clang_format_settings(
+    src: &Path,
+    dst: &Path,
+    version: u32,
+    ending: &[u8],
+    rules: Vec<rules::Rule>,
+) -> Result<(), ParseError> {
     if src.as_os_str().is_empty() || dst.as_os_str().is_empty() {
         return Err(ParseError::UndefinedFilePath);
     }
@@ -89,32 +106,79 @@ fn parse_clang_format_settings(src: &Path, dst: &Path, version: u32) -> Result<(
         return Err(ParseError::EmptyFile);
     }
 
-    let file = fs::File::open(src)?;
-    let reader = std::io::BufReader::new(file);
+    let (content, latin1) = decode_source(&fs::read(src)?);
+
+    let settings = infer_settings(&content, rules);
+    if latin1 {
+        settings.borrow_mut().warnings.insert(0, "The source is not UTF-8 and was read as Latin-1.".to_string());
+    }
+
+    for warning in &settings.borrow().warnings {
+        eprintln!("Warning: {}", warning);
+    }
+
+    create_clang_format_file(settings, dst, version, ending)
+}
 
+// settings of the source, the rules of the user are applied last, so they override the other inferences
+fn infer_settings(content: &str, rules: Vec<rules::Rule>) -> Rc<RefCell<ClangFormatSettings>> {
     let settings = Rc::new(RefCell::new(ClangFormatSettings::new()));
 
-    let mut parser = parser::Impl::new(settings.clone());
+    // the lines don't tell, whether the last one was terminated
+    settings.borrow_mut().empty_lines.insert_newline_at_eof.set(content.ends_with('\n'));
+    set_line_ending(&mut settings.borrow_mut(), content);
 
-    for line in reader.lines() {
-        let line = line?;
-        parser.parse_line(&line);
+    let mut parser = parser::Impl::new(settings.clone(), rules);
+    for line in content.lines() {
+        parser.parse_line(line);
     }
-
     parser.finish();
-
-    create_clang_format_file(settings, &dst, version)
+    settings
 }
 
-
-fn make_reference_file(dst: &Path) -> Result<(), ParseError> {
+fn make_reference_file(dst: &Path, ending: &[u8]) -> Result<(), ParseError> {
     let mut file_content: TextFileContent = vec![];
     generator::generate_reference_file(&mut file_content);
-    if let Err(err) = write_to_file(dst, &file_content) {
-        return Err(ParseError::WriteFileError(err));
-    } else {
-        Ok(())
+    write_to_file(dst, &file_content, ending).map_err(ParseError::WriteFileError)
+}
+
+// Text of the source without a byte order mark, sources which are not UTF-8 are read as Latin-1
+fn decode_source(bytes: &[u8]) -> (String, bool) {
+    if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return (String::from_utf8_lossy(rest).into_owned(), false);
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFF\xFE") {
+        return (decode_utf16(rest, u16::from_le_bytes), false);
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFE\xFF") {
+        return (decode_utf16(rest, u16::from_be_bytes), false);
     }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), false),
+        Err(_) => (bytes.iter().map(|&byte| byte as char).collect(), true),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], convert: fn([u8; 2]) -> u16) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| convert([pair[0], pair[1]]));
+    char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+}
+
+// LineEnding of the source, mixed line endings are derived with the majority as fallback
+fn set_line_ending(settings: &mut ClangFormatSettings, content: &str) {
+    let crlf = content.matches("\r\n").count();
+    let lf = content.matches('\n').count() - crlf;
+    let value = match (crlf, lf) {
+        (0, 0) => return,
+        (0, _) => "LF",
+        (_, 0) => "CRLF",
+        _ if crlf > lf => "DeriveCRLF",
+        _ => "DeriveLF",
+    };
+    let line_endings = &mut settings.line_endings;
+    line_endings.line_ending.set(value);
+    line_endings.derive_line_ending.set(value.starts_with("Derive"));
+    line_endings.use_crlf.set(crlf > lf);
 }
 
 
@@ -134,10 +198,20 @@ fn run() -> ParseResult<()> {
         .arg(Arg::new("reference")
             .long("reference")
             .help("Make reference file"))
+        .arg(Arg::new("rules")
+            .long("rules")
+            .help("TOML file with additional inference rules"))
+        .arg(Arg::new("line-ending")
+            .long("line-ending")
+            .value_parser(["lf", "crlf", "native"])
+            .default_value("native")
+            .help("Line ending of the written file"))
         .get_matches();
 
+    let ending = line_ending(matches.get_one::<String>("line-ending").map_or("native", String::as_str));
+
     if let Some(reference_file) = matches.get_one::<String>("reference") {
-        return make_reference_file(Path::new(reference_file));
+        return make_reference_file(Path::new(reference_file), ending);
     }
 
     let src = matches.get_one::<String>("src").ok_or(ParseError::InvalidArguments)?;
@@ -147,7 +221,14 @@ fn run() -> ParseResult<()> {
     let version = version_str.parse::<u32>()
         .map_err(|_| ParseError::InvalidVersionArgument)?;
 
-    parse_clang_format_settings(Path::new(src), Path::new(dst), version)
+    let mut user_rules = Vec::new();
+    if let Some(rule_file) = matches.get_one::<String>("rules") {
+        let text = fs::read_to_string(rule_file)?;
+        user_rules = rules::parse_rules(&text).map_err(ParseError::InvalidRules)?;
+    }
+    let rules = rules::merge_rules(rules::builtin_rules(), user_rules);
+
+    parse_clang_format_settings(Path::new(src), Path::new(dst), version, ending, rules)
 }
 
 
@@ -157,3 +238,41 @@ fn main() {
         std::process::exit(1);
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_source() {
+        assert_eq!(decode_source(b"\xEF\xBB\xBFint a;\n"), ("int a;\n".to_string(), false));
+        assert_eq!(decode_source(b"\xFF\xFEi\0n\0t\0"), ("int".to_string(), false));
+        assert_eq!(decode_source(b"// Gr\xF6\xDFe\n"), ("// Größe\n".to_string(), true));
+    }
+
+    #[test]
+    fn test_line_ending() {
+        let mut settings = ClangFormatSettings::new();
+        set_line_ending(&mut settings, "int a;\r\nint b;\r\n");
+        assert_eq!(settings.line_endings.line_ending.get_value(), Some(&"CRLF"));
+        assert_eq!(settings.line_endings.derive_line_ending.get_value(), Some(&false));
+        assert_eq!(settings.line_endings.use_crlf.get_value(), Some(&true));
+
+        set_line_ending(&mut settings, "int a;\r\nint b;\nint c;\n");
+        assert_eq!(settings.line_endings.line_ending.get_value(), Some(&"DeriveLF"));
+        assert_eq!(settings.line_endings.derive_line_ending.get_value(), Some(&true));
+        assert_eq!(settings.line_endings.use_crlf.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_user_rules_override_file_properties() {
+        let user = rules::parse_rules("[[rule]]\noption = \"InsertNewlineAtEOF\"\nvalue = false\n");
+        let rules = rules::merge_rules(rules::builtin_rules(), user.unwrap());
+        let settings = infer_settings("int a;\n", rules);
+        assert_eq!(settings.borrow().empty_lines.insert_newline_at_eof.get_value(), Some(&false));
+
+        let settings = infer_settings("int a;\n", rules::builtin_rules());
+        assert_eq!(settings.borrow().empty_lines.insert_newline_at_eof.get_value(), Some(&true));
+    }
+}
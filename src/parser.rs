@@ -1,12 +1,21 @@
 // https://alvalea.gitbooks.io/rust-for-cpp/content/pimpl.html
-use crate::clang_format_lib::{ClangFormatSettings, Parser, ALIGNMENT};
+use crate::rules::{Rule, RuleScope};
+use crate::clang_format_lib::{
+    AlignConsecutiveSettings, ClangFormatSettings, IncludeCategories, IncludeCategory, Parser, StringList,
+    ALIGNMENT,
+};
 use std::rc::Rc;
 use std::cell::RefCell;
+use unicode_width::UnicodeWidthStr;
 
 struct LineInfo {
     line: String,
     settings: Rc<RefCell<ClangFormatSettings>>,
     finished: bool,
+    // the line follows excluded lines, so runs and statements don't continue across the gap
+    region_break: bool,
+    // the line is bound to an option by a @cfg annotation, only structural topics see it
+    annotated: bool,
 }
 
 impl LineInfo {
@@ -17,10 +26,16 @@ impl LineInfo {
     fn without(&self, search: &str) -> bool {
         !self.find(search)
     }
+
+    // line without any whitespace, so that matches don't depend on the spacing style
+    fn compact(&self) -> String {
+        self.line.split_whitespace().collect()
+    }
 }
 
 struct TopicInfo {
     done: bool,
+    structural: bool,
     func: Box<dyn FnMut(&mut LineInfo) -> bool>,
 }
 
@@ -31,308 +46,4211 @@ where
     topics.push(TopicInfo {
         func: Box::new(f),
         done: false,
+        structural: false,
+    });
+}
+
+// a topic, which follows the structure of the code like empty lines, scopes or statements, so it reads
+// annotated lines as well
+fn add_structural_topic<F>(topics: &mut Vec<TopicInfo>, f: F)
+where
+    F: FnMut(&mut LineInfo) -> bool + 'static,
+{
+    topics.push(TopicInfo {
+        func: Box::new(f),
+        done: false,
+        structural: true,
     });
 }
 
 pub(crate) struct Impl {
     settings: Rc<RefCell<ClangFormatSettings>>,
     topics: Vec<TopicInfo>,
+    exclusion: Exclusion,
+    skipped: bool,
+    annotation_rules: Vec<Rule>,
+    annotation: Option<usize>,
+    annotated: Vec<(usize, String)>,
 }
 
 impl Impl {
-    pub fn new(settings: Rc<RefCell<ClangFormatSettings>>) -> Self {
+    pub fn new(settings: Rc<RefCell<ClangFormatSettings>>, rules: Vec<Rule>) -> Self {
         let mut topics = Vec::with_capacity(15);
+        let (annotation_rules, line_rules): (Vec<Rule>, Vec<Rule>) =
+            rules.into_iter().partition(|rule| rule.scope == RuleScope::Annotation);
+        let (user_rules, builtin_rules): (Vec<Rule>, Vec<Rule>) =
+            line_rules.into_iter().partition(|rule| rule.user);
+        for rule in builtin_rules {
+            Self::add_rule_topic(&mut topics, rule);
+        }
         Self::set_topics(&mut topics);
-        Self { settings, topics }
+        Self::set_signature_topics(&mut topics);
+        Self::set_return_type_topics(&mut topics);
+        Self::set_constructor_initializer_topics(&mut topics);
+        Self::set_inheritance_topics(&mut topics);
+        Self::set_template_topics(&mut topics);
+        Self::set_lambda_topics(&mut topics);
+        Self::set_preprocessor_topics(&mut topics);
+        Self::set_include_topics(&mut topics);
+        Self::set_align_consecutive_topics(&mut topics);
+        Self::set_comment_topics(&mut topics);
+        Self::set_operator_topics(&mut topics);
+        Self::set_spacing_topics(&mut topics);
+        Self::set_parentheses_topics(&mut topics);
+        Self::set_braced_list_topics(&mut topics);
+        Self::set_qualifier_topics(&mut topics);
+        Self::set_empty_line_topics(&mut topics);
+        Self::set_cleanup_topics(&mut topics);
+        Self::set_macro_topics(&mut topics);
+        Self::set_breaking_topics(&mut topics);
+        // the rules of the user come last to take precedence over the other topics
+        for rule in user_rules {
+            Self::add_rule_topic(&mut topics, rule);
+        }
+        Self {
+            settings,
+            topics,
+            exclusion: Exclusion::None,
+            skipped: false,
+            annotation_rules,
+            annotation: None,
+            annotated: Vec::new(),
+        }
+    }
+
+    // the first scope is decided by the first line, which has a value
+    fn add_rule_topic(topics: &mut Vec<TopicInfo>, rule: Rule) {
+        let mut decided: Option<String> = None;
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                // rules of the user apply their value again, after the other topics are finished
+                if let Some(line) = decided.as_ref().filter(|_| rule.user) {
+                    rule.apply(line, &mut info.settings.borrow_mut());
+                }
+                return true;
+            }
+            let first_decided = rule.scope == RuleScope::First && decided.is_some();
+            let applies = !first_decided && rule.matches(&info.line);
+            if applies && rule.apply(&info.line, &mut info.settings.borrow_mut()) {
+                decided = Some(info.line.clone());
+            }
+            first_decided && !rule.user
+        });
     }
+
     fn run_checks(&mut self, info: &mut LineInfo) {
         for topic in &mut self.topics {
-            if !topic.done {
+            if !topic.done && (topic.structural || !info.annotated) {
                 topic.done = (topic.func)(info);
             }
         }
     }
 
     fn set_topics(topics: &mut Vec<TopicInfo>) {
-        add_topic(topics, |info: &mut LineInfo| {
-            let result = info.find("namespace") && info.without("// namespace");
-            if result {
-                info.settings.borrow_mut().break_before_braces.after_namespace.set(info.without("{"));
+        let mut line_widths = LineWidths { widths: Vec::new() };
+
+        // without the MAX WIDTH banner, the limit is the cutoff of the line widths
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let mut settings = info.settings.borrow_mut();
+                if settings.column_limit.get_value().is_none() {
+                    if let Some(limit) = column_limit(&line_widths.widths) {
+                        settings.column_limit.set(limit);
+                    }
+                }
+                return true;
             }
-            result
+
+            // URLs, string literals and includes can't be broken, comments are rarely reflowed
+            let line = info.line.trim_end();
+            let unbreakable =
+                line.contains("://") || line.contains('"') || line.trim_start().starts_with("#include");
+            let comment = line.trim_start().starts_with("//") || line.trim_start().starts_with("/*");
+            if !unbreakable && !comment {
+                line_widths.widths.push(display_width(line));
+            }
+            false
         });
     
-        add_topic(topics, |info: &mut LineInfo| {
-            let result = info.find("class");
-            if result {
-                (info.settings.borrow_mut().break_before_braces.after_class).set(info.without("{"));
+        let mut short_functions = ShortFunctions {
+            inline: None,
+            top_level: None,
+            empty: None,
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let value = match (short_functions.inline, short_functions.top_level, short_functions.empty) {
+                    (_, Some(true), _) => "All",
+                    (Some(true), _, Some(true)) => "Inline",
+                    (Some(true), _, _) => "InlineOnly",
+                    (_, _, Some(true)) => "Empty",
+                    (None, None, None) => return true,
+                    _ => "None",
+                };
+                info.settings.borrow_mut().allow_short.functions_on_a_single_line.set(value);
+                return true;
             }
-            result
+
+            let collapsed = info.find("{") && info.find("}");
+            if info.find("get_value") {
+                short_functions.inline = Some(collapsed);
+            } else if info.find("short_function") {
+                short_functions.top_level = Some(collapsed);
+            } else if info.find("empty_function") {
+                short_functions.empty = Some(collapsed);
+            }
+            false
         });
-    
+
+        let mut short_ifs = ShortIfs {
+            simple: None,
+            with_else: None,
+            else_branch: None,
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let value = match (short_ifs.simple, short_ifs.with_else, short_ifs.else_branch) {
+                    (None, _, _) => return true,
+                    (Some(false), _, _) => "Never",
+                    (Some(true), Some(true), Some(true)) => "AllIfsAndElse",
+                    (Some(true), Some(true), _) => "OnlyFirstIf",
+                    _ => "WithoutElse",
+                };
+                let mut settings = info.settings.borrow_mut();
+                settings.allow_short.if_statements_on_a_single_line.set(value);
+                settings.allow_short.if_statements_on_a_single_line_legacy.set(value != "Never");
+                return true;
+            }
+
+            let line = info.compact();
+            if line.starts_with("if(count)") {
+                short_ifs.simple = Some(line.ends_with(';'));
+            } else if line.starts_with("if(count==1)") {
+                short_ifs.with_else = Some(line.ends_with(';'));
+            } else if short_ifs.with_else.is_some() && short_ifs.else_branch.is_none() && line.starts_with("else") {
+                short_ifs.else_branch = Some(line.ends_with(';'));
+            }
+            false
+        });
+
         add_topic(topics, |info: &mut LineInfo| {
-            let result = info.find("struct");
+            let line = info.compact();
+            let result = line.starts_with("while(count)");
             if result {
-                (info.settings.borrow_mut().break_before_braces.after_struct).set(info.without("{"));
+                info.settings.borrow_mut().allow_short.loops_on_a_single_line.set(line.ends_with(';'));
             }
             result
         });
-    
+
         add_topic(topics, |info: &mut LineInfo| {
-            let result = info.find("enum");
+            let line = info.compact();
+            let result = line.starts_with("if(count>1)");
             if result {
-                (info.settings.borrow_mut().break_before_braces.after_enum).set(info.without("{"));
+                let value = if line.ends_with("{}") { "Empty" } else { "Never" };
+                let mut settings = info.settings.borrow_mut();
+                settings.allow_short.blocks_on_a_single_line.set(value);
+                settings.allow_short.blocks_on_a_single_line_legacy.set(false);
             }
             result
         });
-    
+
         add_topic(topics, |info: &mut LineInfo| {
-            let result = info.find("ReferenceClass") && info.without("class");
+            let result = info.find("SHORT_ENUM");
             if result {
-                (info.settings.borrow_mut().break_before_braces.after_function).set(info.without("{"));
+                info.settings.borrow_mut().allow_short.enums_on_a_single_line.set(info.find("}"));
             }
             result
         });
+    }
     
-        add_topic(topics, |info: &mut LineInfo| {
-            let pos = info.line.find("TYPE_A");
-            let result = pos.is_some();
-            if let Some(pos) = pos {
-                info.settings.borrow_mut().indent_width.set(pos as u32);
+
+    fn set_signature_topics(topics: &mut Vec<TopicInfo>) {
+        let mut declaration = Statement::new("long_function_declaration(", &[';', '{']);
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if !declaration.collect(info) {
+                return info.finished;
             }
-            result
+            if let Some(list) = declaration.wrapped_list(info) {
+                let value = if list.break_after_open && list.closing_on_own_line {
+                    "BlockIndent"
+                } else if list.break_after_open {
+                    "AlwaysBreak"
+                } else if list.aligned {
+                    "Align"
+                } else {
+                    "DontAlign"
+                };
+                let mut settings = info.settings.borrow_mut();
+                settings.signatures.align_after_open_bracket.set(value);
+                settings.signatures.bin_pack_parameters.set(list.packed);
+            }
+            true
         });
-    
-        add_topic(topics, |info: &mut LineInfo| {
-            let result = info.find("MAX WIDTH");
-            if result {
-                info.settings.borrow_mut().column_limit.set(info.line.len() as u32);
+
+        let mut call = Statement::new("long_function_declaration(", &[';']);
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if !call.collect(info) {
+                return info.finished;
             }
-            result
+            if !call.contains("first_argument") {
+                call.reset();
+                return false;
+            }
+            if let Some(list) = call.wrapped_list(info) {
+                info.settings.borrow_mut().signatures.bin_pack_arguments.set(list.packed);
+            }
+            true
         });
-    
-        add_topic(topics, |info: &mut LineInfo| {
-            let result = info.find("[");
-            if result {
-                info.settings.borrow_mut().spaces_in_square_brackets.set(info.find("[ 5 ]"));
-                (info.settings.borrow_mut().space_before.space_before_square_brackets).set(info.find(" ["));
+
+        let mut next_line_declaration =
+            Statement::new("function_with_a_long_name_and_parameters_that_fit_on_the_next_line(", &[';', '{']);
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if !next_line_declaration.collect(info) {
+                return info.finished;
             }
-            result
+            if let Some(list) = next_line_declaration.wrapped_list(info) {
+                if list.single_continuation || !list.packed {
+                    (info.settings.borrow_mut().signatures.allow_all_parameters_of_declaration_on_next_line)
+                        .set(list.single_continuation);
+                }
+            }
+            true
         });
-    
-        add_topic(topics, |info: &mut LineInfo| {
-            let result = info.find("=");
-            if result {
-                (info.settings.borrow_mut().space_before.space_before_assignment_operators).set(info.find(" ="));
+
+        let mut next_line_call =
+            Statement::new("function_with_a_long_name_and_parameters_that_fit_on_the_next_line(", &[';']);
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if !next_line_call.collect(info) {
+                return info.finished;
             }
-            result
+            if !next_line_call.contains("first_argument") {
+                next_line_call.reset();
+                return false;
+            }
+            if let Some(list) = next_line_call.wrapped_list(info) {
+                if list.single_continuation || !list.packed {
+                    (info.settings.borrow_mut().signatures.allow_all_arguments_on_next_line)
+                        .set(list.single_continuation);
+                }
+            }
+            true
         });
-    
-        add_topic(topics, |info: &mut LineInfo| {
+
+        let mut previous_line = String::new();
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            let result = info.find("wrapped_function_name_after_a_long_return_type");
+            if result && info.line.trim_start().starts_with("wrapped_function_name_after_a_long_return_type") {
+                (info.settings.borrow_mut().signatures.indent_wrapped_function_names)
+                    .set(indentation(&info.line) > indentation(&previous_line));
+            }
+            previous_line = info.line.clone();
+            result || info.finished
+        });
+    }
+
+    fn set_return_type_topics(topics: &mut Vec<TopicInfo>) {
+        let mut return_types = ReturnTypes {
+            member_declaration: None,
+            member_definition: None,
+            top_level_declaration: None,
+            top_level_definition: None,
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
             if info.finished {
-                if !info.settings.borrow().fix_namespace_comments.is_set() {
-                    info.settings.borrow_mut().fix_namespace_comments.set(false);
-                }
+                let ReturnTypes {
+                    member_declaration: Some(member_declaration),
+                    member_definition: Some(member_definition),
+                    top_level_declaration: Some(top_level_declaration),
+                    top_level_definition: Some(top_level_definition),
+                } = return_types
+                else {
+                    return true;
+                };
+
+                let value = match (member_declaration, member_definition, top_level_declaration, top_level_definition) {
+                    (true, true, true, true) => "All",
+                    (false, _, true, true) => "TopLevel",
+                    (false, true, false, true) => "AllDefinitions",
+                    (false, false, false, true) => "TopLevelDefinitions",
+                    _ => "None",
+                };
+                let definition_value = match (member_definition, top_level_definition) {
+                    (true, true) => "All",
+                    (false, true) => "TopLevel",
+                    _ => "None",
+                };
+
+                let mut settings = info.settings.borrow_mut();
+                settings.return_type.always_break_after_return_type.set(value);
+                settings.return_type.break_after_return_type.set(if value == "None" { "Automatic" } else { value });
+                settings.return_type.always_break_after_definition_return_type.set(definition_value);
                 return true;
             }
-            let result = info.find("// namespace");
-            if result {
-                info.settings.borrow_mut().fix_namespace_comments.set(true);
+
+            // the return type was broken onto its own line, if the line starts with the function name
+            let line = info.line.trim_start();
+            if info.find("member_declaration(") {
+                return_types.member_declaration = Some(line.starts_with("member_declaration("));
+            } else if info.find("member_definition(") {
+                return_types.member_definition = Some(line.starts_with("member_definition("));
+            } else if info.find("top_level_declaration(") {
+                return_types.top_level_declaration = Some(line.starts_with("top_level_declaration("));
+            } else if info.find("top_level_definition(") {
+                return_types.top_level_definition = Some(line.starts_with("top_level_definition("));
             }
-            result
+            false
         });
-    
-        add_topic(topics, |info: &mut LineInfo| {
-            if info.find("int* ") {
-                info.settings.borrow_mut().alignment.pointer_alignment.set(ALIGNMENT::LEFT);
-            } else if info.find("int * ") {
-                info.settings.borrow_mut().alignment.pointer_alignment.set(ALIGNMENT::MIDDLE);
-            } else if info.find("int *") {
-                info.settings.borrow_mut().alignment.pointer_alignment.set(ALIGNMENT::RIGHT);
+    }
+
+    fn set_constructor_initializer_topics(topics: &mut Vec<TopicInfo>) {
+        let mut constructor = Statement::new("ReferenceClass(int*", &['{', ';']);
+        let mut short_constructor = ShortConstructor {
+            on_current_line: None,
+            space_before_colon: None,
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.compact().contains("ReferenceClass(intcount)") {
+                let colon = find_single_colon(&info.line);
+                short_constructor.on_current_line = Some(colon.is_some());
+                if let Some(colon) = colon {
+                    short_constructor.space_before_colon = Some(info.line[..colon].ends_with(' '));
+                }
             }
-            info.settings.borrow().alignment.pointer_alignment.is_set()
-        });
-    
-        add_topic(topics, |info: &mut LineInfo| {
-            if info.find("float& ") {
-                info.settings.borrow_mut().alignment.reference_alignment.set(ALIGNMENT::LEFT);
-            } else if info.find("float & ") {
-                info.settings.borrow_mut().alignment.reference_alignment.set(ALIGNMENT::MIDDLE);
-            } else if info.find("float &") {
-                info.settings.borrow_mut().alignment.reference_alignment.set(ALIGNMENT::RIGHT);
+
+            if !info.finished {
+                constructor.collect(info);
+                return false;
             }
-            info.settings.borrow().alignment.reference_alignment.is_set()
-        });
-    
-        add_topic(topics, |info: &mut LineInfo| {
-            if info.find("( value )") {
-                info.settings.borrow_mut().spaces_in_parens.in_conditional_statements.set(true);
-                info.settings.borrow_mut().spaces_in_parens.spaces_in_conditional_statement.set(true);
-            } else if info.find("(value)") {
-                info.settings.borrow_mut().spaces_in_parens.in_conditional_statements.set(false);
-                info.settings.borrow_mut().spaces_in_parens.spaces_in_conditional_statement.set(false);
+
+            let Some(initializers) = constructor.constructor_initializers() else {
+                return true;
+            };
+
+            let mut settings = info.settings.borrow_mut();
+            let ctor = &mut settings.constructor_initializers;
+            if let Some(style) = initializers.style {
+                ctor.break_constructor_initializers.set(style);
+                ctor.break_constructor_initializers_before_comma.set(style == "BeforeComma");
+                ctor.indent_width.set(initializers.indent_width as u32);
+            }
+
+            let pack = match (initializers.lines_with_items, initializers.packed, short_constructor.on_current_line) {
+                (0, _, _) => None,
+                (1, _, _) => Some("NextLine"),
+                (_, true, _) => Some("BinPack"),
+                (_, false, Some(true)) => Some("CurrentLine"),
+                (_, false, Some(false)) => Some("Never"),
+                _ => None,
+            };
+            if let Some(pack) = pack {
+                ctor.pack_constructor_initializers.set(pack);
+                ctor.all_on_one_line_or_one_per_line.set(pack == "NextLine" || pack == "CurrentLine");
             }
-            info.settings.borrow().spaces_in_parens.in_conditional_statements.is_set()
+
+            if let Some(space) = short_constructor.space_before_colon.or(initializers.space_before_colon) {
+                ctor.space_before_colon.set(space);
+            }
+            true
         });
-    
-        add_topic(topics, |info: &mut LineInfo| {
-            if info.find("(int") {
-                info.settings.borrow_mut().spaces_in_parens.spaces_in_parentheses.set(false);
-                info.settings.borrow_mut().spaces_in_parens.other.set(false);
-            } else if info.find("( int") {
-                info.settings.borrow_mut().spaces_in_parens.spaces_in_parentheses.set(true);
-                info.settings.borrow_mut().spaces_in_parens.other.set(true);
+    }
+
+    fn set_inheritance_topics(topics: &mut Vec<TopicInfo>) {
+        let mut derived = Statement::new("classDerivedClassWithSeveralBases", &['{', ';']);
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if !derived.collect(info) {
+                return info.finished;
+            }
+            if derived.lines.len() < 2 {
+                return true;
+            }
+
+            let lines: Vec<&str> = derived.lines.iter().map(|line| line.trim()).collect();
+            let Some((index, colon)) = lines.iter().enumerate().find_map(|(index, line)| {
+                find_single_colon(line).map(|colon| (index, colon))
+            }) else {
+                return true;
+            };
+
+            // the brace is only on the last line, when it was not wrapped
+            let last = lines[lines.len() - 1];
+            let brace_on_own_line = last == "{";
+            let bases = if brace_on_own_line { &lines[..lines.len() - 1] } else { &lines[..] };
+
+            let style = if lines[index].starts_with(':') {
+                if bases[index + 1..].iter().any(|line| line.starts_with(',')) {
+                    "BeforeComma"
+                } else {
+                    "BeforeColon"
+                }
+            } else if lines[index].ends_with(':') {
+                "AfterColon"
+            } else if bases.len() > index + 1 {
+                "AfterComma"
+            } else {
+                return true;
+            };
+
+            let mut settings = info.settings.borrow_mut();
+            settings.inheritance.break_inheritance_list.set(style);
+            settings.inheritance.break_before_inheritance_comma.set(style == "BeforeComma");
+            if !lines[index].starts_with(':') {
+                settings.inheritance.space_before_inheritance_colon.set(lines[index][..colon].ends_with(' '));
             }
-            info.settings.borrow_mut().spaces_in_parens.spaces_in_parentheses.is_set()
+            settings.break_before_braces.after_class.set(brace_on_own_line);
+            true
         });
-    
+
         add_topic(topics, |info: &mut LineInfo| {
-            let result = info.find("if");
+            let result = info.compact().starts_with("classSingleBase:");
             if result {
-                (info.settings.borrow_mut().break_before_braces.after_control_statement).set(info.without("{"));
+                if let Some(colon) = find_single_colon(&info.line) {
+                    (info.settings.borrow_mut().inheritance.space_before_inheritance_colon)
+                        .set(info.line[..colon].ends_with(' '));
+                }
             }
             result
         });
-    
+    }
+
+    fn set_template_topics(topics: &mut Vec<TopicInfo>) {
         add_topic(topics, |info: &mut LineInfo| {
-            let result = info.find("else");
+            let result = info.compact().contains("template<");
             if result {
-                info.settings.borrow_mut().break_before_braces.before_else.set(info.without("}"));
+                let angle = info.line.find('<').unwrap_or_default();
+                let spaces_in_angles = info.line[angle + 1..].starts_with(' ');
+                let mut settings = info.settings.borrow_mut();
+                settings.templates.space_after_template_keyword.set(info.find("template <"));
+                settings.templates.spaces_in_angles.set(if spaces_in_angles { "Always" } else { "Never" });
+                settings.templates.spaces_in_angles_legacy.set(spaces_in_angles);
             }
             result
         });
-    
-        let mut empty_lines = EmptyLines {
-            max_consecutive_empty_lines: 0,
-            consecutive_empty_lines: 0,
+
+        let mut template_declarations = TemplateDeclarations {
+            short_broken: None,
+            definition_broken: None,
         };
-    
+
         add_topic(topics, move |info: &mut LineInfo| {
             if info.finished {
-                info.settings.borrow_mut().max_empty_lines_to_keep.set(empty_lines.max_consecutive_empty_lines);
+                let value = match (template_declarations.short_broken, template_declarations.definition_broken) {
+                    (Some(true), _) => "Yes",
+                    (Some(false), Some(true)) => "MultiLine",
+                    (Some(false), Some(false)) => "No",
+                    _ => return true,
+                };
+                let mut settings = info.settings.borrow_mut();
+                settings.templates.always_break_template_declarations.set(value);
+                settings.templates.break_template_declarations.set(value);
+                settings.templates.always_break_template_declarations_legacy.set(value == "Yes");
                 return true;
             }
-            if info.line.is_empty() {
-                empty_lines.increment();
-            } else {
-                empty_lines.reset();
+
+            // the declaration was broken after the template, if the function is not on the template line
+            let on_template_line = info.compact().contains("template<");
+            if info.find("short_template_function(") {
+                template_declarations.short_broken = Some(!on_template_line);
+            } else if info.find("template_function(") {
+                template_declarations.definition_broken = Some(!on_template_line);
             }
             false
         });
-    
-        let mut space_after = SpaceAfter {
-            space_after_if: false,
-            space_after_function: false,
-        };
-    
+
+        let mut previous_line = String::new();
+
         add_topic(topics, move |info: &mut LineInfo| {
-            if info.finished || (space_after.space_after_if && space_after.space_after_function) {
-                if space_after.space_after_function && space_after.space_after_if {
-                    info.settings.borrow_mut().space_before_parens.space_before_parens.set("Always");
-                } else if !space_after.space_after_function && !space_after.space_after_if {
-                    info.settings.borrow_mut().space_before_parens.space_before_parens.set("Never");
-                } else if !space_after.space_after_function && space_after.space_after_if {
-                    info.settings.borrow_mut().space_before_parens.space_before_parens.set("ControlStatements");
+            let result = info.compact().contains("requiresIncrementable<T>");
+            if result {
+                let on_template_line = info.compact().contains("template<");
+                let value = match (on_template_line, info.find("constrained_function")) {
+                    (true, true) => "SingleLine",
+                    (true, false) => "WithPreceding",
+                    (false, true) => "WithFollowing",
+                    (false, false) => "OwnLine",
+                };
+                let mut settings = info.settings.borrow_mut();
+                settings.templates.requires_clause_position.set(value);
+                if !on_template_line {
+                    let indented = indentation(&info.line) > indentation(&previous_line);
+                    settings.templates.indent_requires_clause.set(indented);
+                    settings.templates.indent_requires.set(indented);
                 }
-    
-                info.settings.borrow_mut().space_before_parens.after_control_statements.set(space_after.space_after_if);
-                info.settings.borrow_mut().space_before_parens.after_function_definition_name.set(space_after.space_after_function);
-    
-                return true;
-            }
-    
-            if info.find("if (") {
-                space_after.space_after_if = true;
-            } else if info.find("ReferenceClass (") {
-                space_after.space_after_function = true;
             }
-    
-            false
+            previous_line = info.line.clone();
+            result || info.finished
         });
-    }
-    
-}
 
-impl Parser for Impl {
-    fn parse_line(&mut self, line: &str) {
-        let mut info = LineInfo {
-            line: line.to_string(),
-            settings: self.settings.clone(),
-            finished: false,
+        let mut requires_expression = RequiresExpression {
+            concept_indent: None,
+            requires_column: None,
         };
-        self.run_checks(&mut info);
-    }
 
-    fn finish(&mut self) {
-        let mut info = LineInfo {
-            line: "".to_string(),
-            settings: self.settings.clone(),
-            finished: true,
-        };
-        self.run_checks(&mut info);
-    }
-}
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                return true;
+            }
+            if info.compact().starts_with("conceptIncrementable") {
+                requires_expression.concept_indent = Some(indentation(&info.line));
+            }
+            let Some(concept_indent) = requires_expression.concept_indent else {
+                return false;
+            };
 
+            if let Some(requires_column) = requires_expression.requires_column {
+                let body = indentation(&info.line);
+                let value = if requires_column > concept_indent && body > requires_column {
+                    "Keyword"
+                } else {
+                    "OuterScope"
+                };
+                info.settings.borrow_mut().templates.requires_expression_indentation.set(value);
+                return true;
+            }
 
-struct EmptyLines {
-    max_consecutive_empty_lines: u32,
-    consecutive_empty_lines: u32,
-}
+            if let Some(column) = info.line.find("requires") {
+                if info.find("{") {
+                    requires_expression.requires_column = Some(info.line[..column].chars().count());
+                }
+            }
+            false
+        });
 
-impl EmptyLines {
-    fn increment(&mut self) {
-        self.consecutive_empty_lines += 1;
-        if self.consecutive_empty_lines > self.max_consecutive_empty_lines {
-            self.max_consecutive_empty_lines = self.consecutive_empty_lines;
-        }
-    }
+        let mut after_template = false;
 
-    fn reset(&mut self) {
-        self.consecutive_empty_lines = 0;
+        add_topic(topics, move |info: &mut LineInfo| {
+            let code = code_part(&info.line).trim();
+            let concept = code.starts_with("concept ") || code.contains("> concept ") || code.contains(">concept ");
+            let on_template_line = info.compact().starts_with("template<");
+            let result = concept && (on_template_line || after_template);
+            if result {
+                let mut settings = info.settings.borrow_mut();
+                let value = if on_template_line { "Never" } else { "Always" };
+                settings.templates.break_before_concept_declarations.set(value);
+                settings.templates.break_before_concept_declarations_legacy.set(!on_template_line);
+            }
+            after_template = on_template_line;
+            result || info.finished
+        });
     }
-}
 
-struct SpaceAfter {
-    space_after_if: bool,
-    space_after_function: bool,
-}
-
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_lineinfo() {
-        let settings = Rc::new(RefCell::new(ClangFormatSettings::new()));
-        let  info = LineInfo {
-            line: "Hello World".to_string(),
-            settings: settings.clone(),
-            finished: false,
+    fn set_lambda_topics(topics: &mut Vec<TopicInfo>) {
+        let mut short_lambdas = ShortLambdas {
+            assigned: None,
+            argument: None,
+            empty: None,
         };
-        assert!(info.find("World"));
-        assert!(!info.without("World"));
-    }
 
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let value = match (short_lambdas.assigned, short_lambdas.argument, short_lambdas.empty) {
+                    (Some(true), _, _) => "All",
+                    (_, Some(true), _) => "Inline",
+                    (_, _, Some(true)) => "Empty",
+                    (None, None, None) => return true,
+                    _ => "None",
+                };
+                info.settings.borrow_mut().allow_short.lambdas_on_a_single_line.set(value);
+                return true;
+            }
 
-    fn test_add_topic<F>(f: F) {
-        let mut topics: Vec<TopicInfo> = Vec::new();
+            let collapsed = info.find("{") && info.find("}");
+            if info.find("short_lambda") {
+                short_lambdas.assigned = Some(collapsed);
+            } else if info.find("call_with_lambda(") {
+                short_lambdas.argument = Some(collapsed);
+            } else if info.find("empty_lambda") {
+                short_lambdas.empty = Some(collapsed);
+            }
+            false
+        });
 
-        add_topic(&mut topics, |info: &mut LineInfo| {
-            // Example logic that might use info
-            println!("Processing line info");
-            true // Return a boolean value
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.compact().contains("empty_lambda=[]");
+            if result {
+                (info.settings.borrow_mut().space_before_parens.after_function_declaration_name)
+                    .set(info.find("] ("));
+            }
+            result
         });
 
-        // Example of using the stored function
-        if let Some(topic) = topics.first_mut() {
-            let mut line_info = LineInfo {
-                line: "Hello World".to_string(),
-                settings: Rc::new(RefCell::new(ClangFormatSettings::new())),
-                finished: false,
-            };
-            let result = (topic.func)(&mut line_info); // Call the closure
-            println!("Result: {}", result);
+        let mut lambda_body = LambdaBody {
+            statement_indent: None,
+            lambda_indent: None,
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                return true;
+            }
+
+            if info.find("std::for_each(") {
+                lambda_body.statement_indent = Some(indentation(&info.line));
+            }
+            let Some(statement_indent) = lambda_body.statement_indent else {
+                return false;
+            };
+
+            let Some(lambda_indent) = lambda_body.lambda_indent else {
+                if info.find("[&counter") {
+                    if info.find("std::for_each(") {
+                        // the lambda was not wrapped, so the indentation doesn't tell anything
+                        return true;
+                    }
+                    lambda_body.lambda_indent = Some(indentation(&info.line));
+                    info.settings.borrow_mut().break_before_braces.before_lambda_body.set(info.without("{"));
+                }
+                return false;
+            };
+
+            if info.line.trim() == "{" {
+                return false;
+            }
+
+            let body = indentation(&info.line);
+            let value = if body > lambda_indent { "Signature" } else { "OuterScope" };
+            if lambda_indent > statement_indent && body > statement_indent {
+                info.settings.borrow_mut().lambda_body_indentation.set(value);
+            }
+            true
+        });
+    }
+
+    fn set_preprocessor_topics(topics: &mut Vec<TopicInfo>) {
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.compact().starts_with("#ifdefNESTED_FEATURE");
+            if result {
+                let before_hash = indentation(&info.line);
+                let after_hash = indentation(&info.line.trim_start()[1..]);
+                let (value, width) = if before_hash > 0 {
+                    ("BeforeHash", before_hash)
+                } else if after_hash > 0 {
+                    ("AfterHash", after_hash)
+                } else {
+                    ("None", 0)
+                };
+                let mut settings = info.settings.borrow_mut();
+                settings.preprocessor.indent_pp_directives.set(value);
+                if width > 0 {
+                    settings.preprocessor.pp_indent_width.set(width as u32);
+                }
+            }
+            result
+        });
+
+        let mut escaped_macro = Statement::new("#defineMULTI_LINE_MACRO", &[]);
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            let continued = escaped_macro.lines.last().is_some_and(|line| line.ends_with('\\'));
+            if !info.finished && (escaped_macro.lines.is_empty() || continued) {
+                escaped_macro.collect(info);
+                return false;
+            }
+
+            // a macro, which fits on a single line, has no escaped newlines
+            let lines = &escaped_macro.lines;
+            let Some((last, escaped)) = lines.split_last().filter(|(_, escaped)| !escaped.is_empty()) else {
+                return true;
+            };
+            let columns: Vec<usize> = escaped.iter().map(|line| line.chars().count() - 1).collect();
+            let content = escaped
+                .iter()
+                .map(|line| line.trim_end_matches('\\').trim_end().chars().count())
+                .max()
+                .unwrap_or_default();
+            let last_width = last.trim_end().chars().count();
+            let limit = info.settings.borrow().column_limit.get_value().map(|&limit| limit as usize);
+
+            let value = if columns.iter().any(|&column| column != columns[0]) {
+                "DontAlign"
+            } else if limit.is_some_and(|limit| columns[0] + 1 >= limit) {
+                "Right"
+            } else if last_width > content && columns[0] == last_width + 1 {
+                "LeftWithLastLine"
+            } else {
+                "Left"
+            };
+
+            let mut settings = info.settings.borrow_mut();
+            settings.preprocessor.align_escaped_newlines.set(value);
+            settings.preprocessor.align_escaped_newlines_left.set(value.starts_with("Left"));
+            true
+        });
+
+        let mut macro_blocks = MacroBlocks {
+            previous: None,
+            open: Vec::new(),
+            begin: Vec::new(),
+            end: Vec::new(),
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                if !macro_blocks.begin.is_empty() {
+                    let mut settings = info.settings.borrow_mut();
+                    settings.preprocessor.macro_block_begin.set(macro_regex(&macro_blocks.begin));
+                    settings.preprocessor.macro_block_end.set(macro_regex(&macro_blocks.end));
+                }
+                return true;
+            }
+            if info.line.trim().is_empty() {
+                return false;
+            }
+
+            let indent = indentation(&info.line);
+            let name = macro_invocation(&info.line);
+
+            // a block ends with a macro at the indentation of the macro which opened it
+            if let Some(position) = macro_blocks.open.iter().rposition(|(_, open_indent)| *open_indent == indent) {
+                if let Some(name) = &name {
+                    let (begin, _) = macro_blocks.open.remove(position);
+                    if !macro_blocks.begin.contains(&begin) {
+                        macro_blocks.begin.push(begin);
+                    }
+                    if !macro_blocks.end.contains(name) {
+                        macro_blocks.end.push(name.clone());
+                    }
+                }
+            }
+            macro_blocks.open.retain(|(_, open_indent)| *open_indent < indent);
+
+            // a block begins with a macro, which is followed by deeper indented lines
+            if let Some((previous, previous_indent)) = macro_blocks.previous.take() {
+                if indent > previous_indent {
+                    macro_blocks.open.push((previous, previous_indent));
+                }
+            }
+            macro_blocks.previous = name.map(|name| (name, indent));
+            false
+        });
+    }
+
+    fn set_include_topics(topics: &mut Vec<TopicInfo>) {
+        let mut include_groups: Vec<Vec<String>> = Vec::new();
+        let mut in_group = false;
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                if !include_groups.is_empty() {
+                    set_include_settings(&include_groups, &mut info.settings.borrow_mut());
+                }
+                return true;
+            }
+
+            // includes separated by a blank line or any other line are in different groups
+            match include_name(&info.line) {
+                Some(name) => {
+                    if !in_group {
+                        include_groups.push(Vec::new());
+                    }
+                    include_groups.last_mut().unwrap().push(name);
+                    in_group = true;
+                }
+                None => in_group = false,
+            }
+            false
+        });
+    }
+
+    fn set_align_consecutive_topics(topics: &mut Vec<TopicInfo>) {
+        let mut assignments = AlignmentRun::new([
+            "assign_short ",
+            "assign_longer_name",
+            "assign_after_empty_line",
+            "assign_after_comment",
+        ]);
+        let mut compound: Option<(usize, usize)> = None;
+        let mut plain: Option<(usize, usize)> = None;
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let mut settings = info.settings.borrow_mut();
+                let run = &mut settings.align_consecutive.assignments;
+                assignments.set(run);
+                if let (Some(plain), Some(compound), Some(true)) = (plain, compound, run.enabled.get_value()) {
+                    // with padded operators the '=' are aligned, otherwise the start of the operators
+                    let align_compound = plain.1 == compound.1 || plain.0 == compound.0;
+                    run.align_compound.set(align_compound);
+                    run.pad_operators.set(!align_compound || plain.1 == compound.1);
+                }
+                return true;
+            }
+
+            if info.find("assign_longer_name") {
+                plain = find_assignment(&info.line);
+            } else if info.find("assign_short") && info.without("int") {
+                compound = find_assignment(&info.line);
+            }
+            assignments.collect(&info.line, |line, _| find_assignment(line).map(|(_, equal)| equal));
+            false
+        });
+
+        let mut declarations = AlignmentRun::new([
+            "declared_int",
+            "declared_double",
+            "declared_after_empty_line",
+            "declared_after_comment",
+        ]);
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                declarations.set(&mut info.settings.borrow_mut().align_consecutive.declarations);
+                return true;
+            }
+            declarations.collect(&info.line, |line, marker| line.find(marker));
+            false
+        });
+
+        let mut bit_fields =
+            AlignmentRun::new(["bit_short", "bit_longer_name", "bit_after_empty_line", "bit_after_comment"]);
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                bit_fields.set(&mut info.settings.borrow_mut().align_consecutive.bit_fields);
+                return true;
+            }
+            bit_fields.collect(&info.line, |line, _| find_single_colon(line));
+            false
+        });
+
+        let mut macros = AlignmentRun::new([
+            "MACRO_SHORT",
+            "MACRO_LONGER_NAME",
+            "MACRO_AFTER_EMPTY_LINE",
+            "MACRO_AFTER_COMMENT",
+        ]);
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                macros.set(&mut info.settings.borrow_mut().align_consecutive.macros);
+                return true;
+            }
+            macros.collect(&info.line, |line, marker| {
+                let name_end = line.find(marker)? + marker.len();
+                Some(name_end + indentation(&line[name_end..]))
+            });
+            false
+        });
+    }
+
+    fn set_comment_topics(topics: &mut Vec<TopicInfo>) {
+        let mut namespace_ends = NamespaceEnds {
+            pending: false,
+            blocks: Vec::new(),
+            comments: Vec::new(),
+        };
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let comments = &namespace_ends.comments;
+                let mut styles: Vec<&str> = comments.iter().flatten().copied().collect();
+                let commented = styles.len();
+                styles.sort();
+                styles.dedup();
+
+                let mut settings = info.settings.borrow_mut();
+                settings.fix_namespace_comments.set(commented > 0);
+                if commented > 0 && commented < comments.len() {
+                    let warning = format!(
+                        "{} of {} namespaces are closed without a namespace comment.",
+                        comments.len() - commented,
+                        comments.len()
+                    );
+                    settings.warnings.push(warning);
+                }
+                if styles.len() > 1 {
+                    let warning = format!("Namespaces are closed with different comments: {}.", styles.join(", "));
+                    settings.warnings.push(warning);
+                }
+                return true;
+            }
+
+            let code = code_part(&info.line);
+            if code.contains("namespace") && !code.contains("using") && !code.contains(';') {
+                namespace_ends.pending = true;
+            }
+            for c in code.chars() {
+                if c == '{' {
+                    namespace_ends.blocks.push(namespace_ends.pending);
+                    namespace_ends.pending = false;
+                } else if c == '}' && namespace_ends.blocks.pop() == Some(true) {
+                    namespace_ends.comments.push(namespace_comment(&info.line));
+                }
+            }
+            false
+        });
+
+        let mut trailing_comments =
+            AlignmentRun::new(["comment_short(", "comment_longer_name(", "comment_after_empty_line("]);
+        let mut spaces_before: Option<usize> = None;
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let [Some(first), Some(second), after_empty_line] = trailing_comments.columns else {
+                    return true;
+                };
+                let aligned = first == second;
+                let over_empty_lines = aligned && after_empty_line == Some(first);
+
+                let mut settings = info.settings.borrow_mut();
+                settings.comments.align_trailing_comments.set(aligned);
+                settings.comments.align_trailing_comments_kind.set(if aligned { "Always" } else { "Never" });
+                settings.comments.align_trailing_comments_over_empty_lines.set(over_empty_lines as u32);
+                if let Some(spaces) = spaces_before {
+                    settings.comments.spaces_before_trailing_comments.set(spaces as u32);
+                }
+                return true;
+            }
+
+            if trailing_comments.markers.iter().any(|marker| info.find(marker)) {
+                if let Some(comment) = info.line.find("//") {
+                    let spaces = comment - info.line[..comment].trim_end().len();
+                    spaces_before = Some(spaces_before.map_or(spaces, |before| before.min(spaces)));
+                }
+            }
+            trailing_comments.collect(&info.line, |line, _| line.find("//").map(|comment| line[..comment].chars().count()));
+            false
+        });
+
+        let mut prefix_spaces: Option<(usize, usize)> = None;
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                if let Some((minimum, maximum)) = prefix_spaces {
+                    let mut settings = info.settings.borrow_mut();
+                    settings.comments.spaces_in_line_comment_prefix_minimum.set(minimum as u32);
+                    // only limit the spaces, if all comments agree
+                    let maximum = if minimum == maximum { maximum as i32 } else { -1 };
+                    settings.comments.spaces_in_line_comment_prefix_maximum.set(maximum);
+                }
+                return true;
+            }
+
+            let Some(comment) = info.line.find("//") else {
+                return false;
+            };
+            let text = &info.line[comment + 2..];
+            // skip banners, documentation comments, empty comments and urls
+            let url = info.line[..comment].ends_with(':');
+            if text.starts_with('/') || text.starts_with('!') || text.trim().is_empty() || url {
+                return false;
+            }
+            let spaces = indentation(text);
+            prefix_spaces = Some(prefix_spaces.map_or((spaces, spaces), |(minimum, maximum)| {
+                (minimum.min(spaces), maximum.max(spaces))
+            }));
+            false
+        });
+
+        let mut long_comment: Option<bool> = None;
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                return true;
+            }
+            if let Some(fits) = long_comment {
+                let continued = info.line.trim_start().starts_with("//");
+                if fits && continued {
+                    let mut settings = info.settings.borrow_mut();
+                    settings.comments.reflow_comments.set(true);
+                    settings.comments.reflow_comments_mode.set("Always");
+                }
+                return true;
+            }
+
+            if info.find("longer than the column limit") {
+                let limit = info.settings.borrow().column_limit.get_value().copied();
+                let fits = limit.is_none_or(|limit| display_width(&info.line) <= limit as usize);
+                if !fits {
+                    let mut settings = info.settings.borrow_mut();
+                    settings.comments.reflow_comments.set(false);
+                    settings.comments.reflow_comments_mode.set("Never");
+                    return true;
+                }
+                long_comment = Some(fits);
+            }
+            false
+        });
+    }
+
+    fn set_operator_topics(topics: &mut Vec<TopicInfo>) {
+        let mut operators = BinaryOperators {
+            boolean: Statement::new("long_boolean_expression=", &[';']),
+            arithmetic: Statement::new("long_arithmetic_expression=", &[';']),
+            assignment: Statement::new("result_of_a_long_assignment_statement", &[';']),
+        };
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if !info.finished {
+                operators.boolean.collect(info);
+                operators.arithmetic.collect(info);
+                operators.assignment.collect(info);
+                return false;
+            }
+
+            let boolean = operators.boolean.wrapped_operators();
+            let arithmetic = operators.arithmetic.wrapped_operators();
+            let assignment_broken_before =
+                operators.assignment.lines.get(1).map(|line| line.trim_start().starts_with('='));
+
+            let mut settings = info.settings.borrow_mut();
+            let before = boolean.as_ref().or(arithmetic.as_ref()).map(|operators| operators.break_before);
+            let value = match (assignment_broken_before, before) {
+                (Some(true), _) => Some("All"),
+                (_, Some(true)) => Some("NonAssignment"),
+                (_, Some(false)) => Some("None"),
+                _ => None,
+            };
+            if let Some(value) = value {
+                settings.operators.break_before_binary_operators.set(value);
+                settings.operators.break_before_binary_operators_legacy.set(value != "None");
+            }
+
+            if let Some(boolean) = &boolean {
+                settings.operators.align_operands.set(boolean.align_operands);
+                settings.operators.align_operands_legacy.set(boolean.align_operands != "DontAlign");
+            }
+
+            if let Some(arithmetic) = &arithmetic {
+                // RespectPrecedence keeps the products together, OnePerLine breaks them as well
+                let products_broken = operators.arithmetic.lines.iter().any(|line| {
+                    let line = line.trim();
+                    line.starts_with('*') || line.ends_with('*')
+                });
+                let value = if arithmetic.packed {
+                    "Never"
+                } else if products_broken {
+                    "OnePerLine"
+                } else {
+                    "RespectPrecedence"
+                };
+                settings.operators.break_binary_operations.set(value);
+            }
+            true
+        });
+
+        let mut ternary = Statement::new("long_ternary_expression=", &[';']);
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if !ternary.collect(info) {
+                return info.finished;
+            }
+            let continuations = &ternary.lines[1..];
+            if continuations.iter().any(|line| line.trim_start().starts_with(['?', ':'])) {
+                info.settings.borrow_mut().operators.break_before_ternary_operators.set(true);
+            } else if ternary.lines.iter().any(|line| line.trim_end().ends_with(['?', ':'])) {
+                info.settings.borrow_mut().operators.break_before_ternary_operators.set(false);
+            }
+            true
+        });
+    }
+
+    fn set_spacing_topics(topics: &mut Vec<TopicInfo>) {
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.find("cast_value");
+            if result {
+                info.settings.borrow_mut().spacing.space_after_c_style_cast.set(info.find(") count"));
+            }
+            result
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.find("negated_value");
+            if result {
+                info.settings.borrow_mut().spacing.space_after_logical_not.set(info.find("! count"));
+            }
+            result
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.find("for (int value") || info.find("for(int value");
+            if let Some((before, _)) = colon_spacing(&info.line).filter(|_| result) {
+                info.settings.borrow_mut().space_before.space_before_range_based_for_loop_colon.set(before);
+            }
+            result
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.line.trim_start().starts_with("case 1");
+            if let Some((before, _)) = colon_spacing(&info.line).filter(|_| result) {
+                info.settings.borrow_mut().space_before.space_before_case_colon.set(before);
+            }
+            result
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.find("single_bit");
+            if let Some(spacing) = colon_spacing(&info.line).filter(|_| result) {
+                let value = match spacing {
+                    (true, true) => "Both",
+                    (true, false) => "Before",
+                    (false, true) => "After",
+                    (false, false) => "None",
+                };
+                info.settings.borrow_mut().spacing.bit_field_colon_spacing.set(value);
+            }
+            result
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            let line = info.compact();
+            let empty_block = line.starts_with("voidempty_function()") || line.starts_with("while(count>2)");
+            let result = empty_block && line.ends_with('}');
+            if result {
+                info.settings.borrow_mut().spacing.space_in_empty_block.set(info.find("{ }"));
+            }
+            result
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.find("qualified_pointer");
+            if result {
+                let mut settings = info.settings.borrow_mut();
+                // spaces around the qualifier, which the pointer alignment doesn't produce on its own
+                let alignment = settings.alignment.pointer_alignment.get_value();
+                let default_before = alignment.is_some_and(|alignment| *alignment != ALIGNMENT::RIGHT);
+                let default_after = alignment.is_some_and(|alignment| *alignment != ALIGNMENT::LEFT);
+                let before = info.find("* const") && !default_before;
+                let after = info.find("const *") && !default_after;
+                let value = match (before, after) {
+                    (true, true) => "Both",
+                    (true, false) => "Before",
+                    (false, true) => "After",
+                    (false, false) => "Default",
+                };
+                settings.spacing.space_around_pointer_qualifiers.set(value);
+            }
+            result
+        });
+    }
+
+    fn set_parentheses_topics(topics: &mut Vec<TopicInfo>) {
+        let mut space_before = SpaceBeforeParens::default();
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let mut settings = info.settings.borrow_mut();
+                let parens = &mut settings.space_before_parens;
+                // lambdas follow the declaration name option as well
+                let lambda = parens.after_function_declaration_name.get_value().copied();
+                space_before.function_declaration_name = space_before.function_declaration_name.or(lambda);
+                let mode = space_before.mode();
+                parens.space_before_parens.set(mode);
+                parens.space_before_parens_fallback.set(space_before.fallback());
+
+                // the named modes imply the options, a custom mode lists the observed ones
+                let control = space_before.control_statements.unwrap_or(mode == "Always");
+                let any = |observed: Option<bool>| observed.unwrap_or(mode == "Always");
+                parens.after_control_statements.set(control);
+                parens.after_foreach_macros.set(any(space_before.foreach_macros));
+                parens.after_function_declaration_name.set(any(space_before.function_declaration_name));
+                parens.after_function_definition_name.set(any(space_before.function_definition_name));
+                parens.after_if_macros.set(any(space_before.if_macros));
+                parens.after_overloaded_operator.set(any(space_before.overloaded_operator));
+                parens.after_placement_operator.set(space_before.placement_operator.unwrap_or(true));
+                parens.after_requires_in_clause.set(any(space_before.requires_in_clause));
+                parens.after_requires_in_expression.set(any(space_before.requires_in_expression));
+                parens.before_non_empty_parentheses.set(space_before.non_empty_parentheses.unwrap_or(false));
+                return true;
+            }
+
+            let line = info.line.trim_start();
+            let observations = [
+                (&mut space_before.foreach_macros, "BOOST_FOREACH"),
+                (&mut space_before.if_macros, "KJ_IF_MAYBE"),
+                (&mut space_before.function_declaration_name, "top_level_declaration"),
+                (&mut space_before.function_definition_name, "top_level_definition"),
+                (&mut space_before.overloaded_operator, "operator=="),
+                (&mut space_before.placement_operator, "= new"),
+                (&mut space_before.requires_in_expression, "= requires"),
+                (&mut space_before.non_empty_parentheses, "call_with_arguments"),
+                (&mut space_before.empty_parentheses, "call_without_arguments"),
+            ];
+            for (observed, name) in observations {
+                if let Some(spaced) = space_before_parenthesis(line, name) {
+                    observed.get_or_insert(spaced);
+                }
+            }
+            if line.starts_with("if") {
+                space_before.control_statements.get_or_insert(line.starts_with("if ("));
+            }
+            if line.starts_with("requires") && line.contains("sizeof") {
+                space_before.requires_in_clause = Some(line.starts_with("requires ("));
+            }
+            false
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.find("cast_value");
+            if result {
+                let spaced = info.find("( double )");
+                let mut settings = info.settings.borrow_mut();
+                settings.spaces_in_parens.in_c_style_casts.set(spaced);
+                settings.spaces_in_parens.spaces_in_c_style_cast_parentheses.set(spaced);
+            }
+            result
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.find("call_without_arguments");
+            if result {
+                let spaced = info.find("( )");
+                let mut settings = info.settings.borrow_mut();
+                settings.spaces_in_parens.in_empty_parentheses.set(spaced);
+                settings.spaces_in_parens.space_in_empty_parentheses.set(spaced);
+            }
+            result
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            if !info.finished {
+                let other = info.settings.borrow().spaces_in_parens.other.get_value() == Some(&true);
+                if other && info.find("__attribute__") {
+                    info.settings.borrow_mut().spaces_in_parens.except_double_parentheses.set(info.find("(("));
+                }
+                return false;
+            }
+
+            let mut settings = info.settings.borrow_mut();
+            let parens = &mut settings.spaces_in_parens;
+            let options = [
+                parens.in_conditional_statements.get_value(),
+                parens.in_c_style_casts.get_value(),
+                parens.in_empty_parentheses.get_value(),
+                parens.other.get_value(),
+            ];
+            let value = if options.contains(&Some(&true)) { "Custom" } else { "Never" };
+            parens.spaces_in_parens.set(value);
+            true
+        });
+    }
+
+    fn set_braced_list_topics(topics: &mut Vec<TopicInfo>) {
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.find("braced_list") && info.find("{");
+            if result {
+                let space_before = info.find("braced_list {");
+                info.settings.borrow_mut().space_before.space_before_cpp11_braced_list.set(space_before);
+                // spaces inside the braces come from SpacesInParens as well in the C++11 style
+                let spaced = info.find("{ 1");
+                let other = info.settings.borrow().spaces_in_parens.other.get_value() == Some(&true);
+                if !(spaced && other) {
+                    info.settings.borrow_mut().braced_lists.cpp11_braced_list_style.set(!spaced);
+                }
+            }
+            result
+        });
+
+        let mut tables = ArrayTables {
+            rows: Vec::new(),
+            in_table: false,
+            alignment: None,
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let mut settings = info.settings.borrow_mut();
+                if let Some(alignment) = tables.alignment {
+                    settings.braced_lists.align_array_of_structures.set(alignment);
+                }
+                return true;
+            }
+
+            let code = code_part(&info.line).trim();
+            if !tables.in_table {
+                tables.in_table = code.ends_with("= {") || code.ends_with("[] =");
+                return false;
+            }
+            if code == "{" && tables.rows.is_empty() {
+                return false;
+            }
+            if code.starts_with('{') && code.trim_end_matches(',').ends_with('}') {
+                tables.rows.push(info.line.clone());
+            } else if code.starts_with('}') {
+                if tables.rows.len() > 1 {
+                    tables.alignment = tables.alignment.or_else(|| table_alignment(&tables.rows));
+                }
+                tables.rows.clear();
+                tables.in_table = false;
+            } else if !code.is_empty() {
+                tables.rows.clear();
+                tables.in_table = false;
+            }
+            false
+        });
+    }
+
+    fn set_qualifier_topics(topics: &mut Vec<TopicInfo>) {
+        let mut qualifiers = Qualifiers {
+            west: 0,
+            east: 0,
+            orders: Vec::new(),
+            pointers: Vec::new(),
+            probes: (None, None),
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let mut settings = info.settings.borrow_mut();
+                if !qualifiers.pointers.is_empty() {
+                    let mixed = qualifiers.pointers.iter().any(|pointer| *pointer != qualifiers.pointers[0]);
+                    settings.alignment.derive_pointer_alignment.set(mixed);
+                }
+
+                // the east and west const probes of the reference end up on the same side, unless they are left
+                let alignment = match (qualifiers.probes, qualifiers.west, qualifiers.east) {
+                    ((Some(true), Some(true)), _, _) => "Left",
+                    ((Some(false), Some(false)), _, _) => "Right",
+                    ((Some(_), Some(_)), _, _) => "Leave",
+                    (_, 0, 0) => return true,
+                    (_, _, 0) => "Left",
+                    (_, 0, _) => "Right",
+                    _ => "Leave",
+                };
+                // a custom order is only used, if the other specifiers are consistently ordered as well and
+                // differ from the order, which the alignment produces on its own
+                let specifiers =
+                    qualifiers.orders.iter().flatten().any(|name| !matches!(*name, "const" | "volatile" | "type"));
+                match merge_qualifier_orders(&qualifiers.orders) {
+                    Some(order)
+                        if specifiers && alignment != "Leave" && !aligned_qualifier_order(&order, alignment) =>
+                    {
+                        let order = order.into_iter().map(String::from).collect();
+                        settings.qualifiers.qualifier_alignment.set("Custom");
+                        settings.qualifiers.qualifier_order.set(StringList(order));
+                    }
+                    _ => settings.qualifiers.qualifier_alignment.set(alignment),
+                }
+                return true;
+            }
+
+            let line = info.line.trim_start();
+            if line.starts_with(['#', '*']) {
+                return false;
+            }
+            for segment in code_part(line).split(['(', ',', ';', '{', '}', '=', ')', '<']) {
+                if let Some(order) = qualifier_order(segment) {
+                    let type_position = order.iter().position(|name| *name == "type").unwrap_or(0);
+                    for (index, name) in order.iter().enumerate() {
+                        if matches!(*name, "const" | "volatile") {
+                            if index < type_position {
+                                qualifiers.west += 1;
+                            } else {
+                                qualifiers.east += 1;
+                            }
+                        }
+                    }
+                    let west =
+                        order.iter().position(|name| *name == "const").is_some_and(|index| index < type_position);
+                    if info.find("qualifier_west") {
+                        qualifiers.probes.0 = Some(west);
+                    } else if info.find("qualifier_east") {
+                        qualifiers.probes.1 = Some(west);
+                    }
+                    qualifiers.orders.push(order);
+                }
+                if let Some(pointer) = pointer_placement(segment) {
+                    qualifiers.pointers.push(pointer);
+                }
+            }
+            false
+        });
+    }
+
+    fn set_empty_line_topics(topics: &mut Vec<TopicInfo>) {
+        let mut empty_lines = EmptyLines {
+            runs: Vec::new(),
+            current: 0,
+            at_start_of_file: None,
+            opens_block: false,
+            at_start_of_block: None,
+        };
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let mut settings = info.settings.borrow_mut();
+                settings.max_empty_lines_to_keep.set(max_empty_lines(&empty_lines.runs));
+
+                let at_end_of_file = empty_lines.current > 0;
+                settings.empty_lines.keep_empty_lines_at_eof.set(at_end_of_file);
+                settings.empty_lines.at_end_of_file.set(at_end_of_file);
+                if let Some(at_start_of_file) = empty_lines.at_start_of_file {
+                    settings.empty_lines.at_start_of_file.set(at_start_of_file);
+                }
+                if let Some(at_start_of_block) = empty_lines.at_start_of_block {
+                    settings.empty_lines.keep_empty_lines_at_the_start_of_blocks.set(at_start_of_block);
+                    settings.empty_lines.at_start_of_block.set(at_start_of_block);
+                }
+                return true;
+            }
+
+            // empty lines before and after an excluded region are separate runs
+            if info.region_break && empty_lines.current > 0 {
+                empty_lines.runs.push(empty_lines.current);
+                empty_lines.current = 0;
+            }
+            let empty = info.line.trim().is_empty();
+            if empty {
+                empty_lines.current += 1;
+            } else if empty_lines.at_start_of_file.is_none() {
+                empty_lines.at_start_of_file = Some(empty_lines.current > 0);
+            } else if empty_lines.current > 0 {
+                empty_lines.runs.push(empty_lines.current);
+            }
+
+            if empty_lines.opens_block {
+                empty_lines.at_start_of_block = Some(empty_lines.at_start_of_block == Some(true) || empty);
+            }
+            if !empty {
+                empty_lines.current = 0;
+                empty_lines.opens_block = code_part(&info.line).trim_end().ends_with('{');
+            } else {
+                empty_lines.opens_block = false;
+            }
+            false
+        });
+
+        let mut definitions = DefinitionBlocks {
+            scopes: Vec::new(),
+            previous: String::new(),
+            in_macro: false,
+            ended: false,
+            empty_line: false,
+            separated: 0,
+            adjacent: 0,
+        };
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let value = match (definitions.separated, definitions.adjacent) {
+                    (0, 0) => return true,
+                    (_, 0) => "Always",
+                    (0, _) => "Never",
+                    _ => "Leave",
+                };
+                info.settings.borrow_mut().empty_lines.separate_definition_blocks.set(value);
+                return true;
+            }
+
+            let line = info.line.trim();
+            if definitions.in_macro || line.starts_with('#') {
+                definitions.in_macro = line.ends_with('\\');
+                return false;
+            }
+            let code = code_part(line).trim();
+            if line.is_empty() {
+                definitions.empty_line = true;
+            }
+            if code.is_empty() {
+                return false;
+            }
+
+            // a definition is followed by another one, not by the end of the scope or a continuation
+            if definitions.ended {
+                let continuation = code.starts_with(['}', ')'])
+                    || access_modifier(code)
+                    || ["else", "while", "catch"].iter().any(|keyword| code.starts_with(keyword));
+                if !continuation {
+                    if definitions.empty_line {
+                        definitions.separated += 1;
+                    } else {
+                        definitions.adjacent += 1;
+                    }
+                }
+                definitions.ended = false;
+            }
+            definitions.empty_line = false;
+
+            for (index, c) in code.char_indices() {
+                if c == '{' {
+                    let before = code[..index].trim();
+                    let statement = if before.is_empty() { definitions.previous.as_str() } else { before };
+                    let scope = scope_kind(statement, definitions.scopes.last().copied());
+                    definitions.scopes.push(scope);
+                } else if c == '}' {
+                    let scope = definitions.scopes.pop();
+                    definitions.ended = matches!(scope, Some(Scope::Class | Scope::Function));
+                }
+            }
+            definitions.previous = code.to_string();
+            false
+        });
+
+        let mut access_modifiers = AccessModifiers {
+            context: AccessContext::Start,
+            empty_line: false,
+            after_code: Vec::new(),
+            after_comment: None,
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let after_code = &access_modifiers.after_code;
+                if after_code.is_empty() {
+                    return true;
+                }
+                let value = if after_code.iter().all(|empty| *empty) {
+                    match access_modifiers.after_comment {
+                        Some(true) => "Always",
+                        _ => "LogicalBlock",
+                    }
+                } else if after_code.iter().all(|empty| !*empty) && access_modifiers.after_comment != Some(true) {
+                    "Never"
+                } else {
+                    "Leave"
+                };
+                info.settings.borrow_mut().empty_lines.empty_line_before_access_modifier.set(value);
+                return true;
+            }
+
+            let line = info.line.trim();
+            if line.is_empty() {
+                access_modifiers.empty_line = true;
+                return false;
+            }
+
+            let code = code_part(line).trim();
+            if access_modifier(code) {
+                match access_modifiers.context {
+                    AccessContext::Code => access_modifiers.after_code.push(access_modifiers.empty_line),
+                    AccessContext::Comment => {
+                        access_modifiers.after_comment.get_or_insert(access_modifiers.empty_line);
+                    }
+                    _ => {}
+                }
+                access_modifiers.context = AccessContext::AccessModifier;
+            } else if code.is_empty() {
+                access_modifiers.context = AccessContext::Comment;
+            } else if code.ends_with('{') {
+                access_modifiers.context = AccessContext::Start;
+            } else {
+                access_modifiers.context = AccessContext::Code;
+            }
+            access_modifiers.empty_line = false;
+            false
+        });
+    }
+
+    fn set_cleanup_topics(topics: &mut Vec<TopicInfo>) {
+        let mut bodies = ControlBodies {
+            in_macro: false,
+            awaiting_body: false,
+            braced_statements: None,
+            braced: 0,
+            unbraced: 0,
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let value = match (bodies.braced, bodies.unbraced) {
+                    (0, 0) => return true,
+                    (_, 0) => (true, false),
+                    (0, _) => (false, true),
+                    _ => (false, false),
+                };
+                let mut settings = info.settings.borrow_mut();
+                settings.cleanup.insert_braces.set(value.0);
+                settings.cleanup.remove_braces_llvm.set(value.1);
+                return true;
+            }
+
+            let line = info.line.trim();
+            if bodies.in_macro || line.starts_with('#') {
+                bodies.in_macro = line.ends_with('\\');
+                return false;
+            }
+            let code = code_part(line).trim();
+            if code.is_empty() {
+                return false;
+            }
+
+            // a braced body with a single statement could do without the braces
+            if let Some(statements) = bodies.braced_statements {
+                if code.starts_with('}') {
+                    if statements == 1 {
+                        bodies.braced += 1;
+                    }
+                    bodies.braced_statements = None;
+                } else if code.contains('{') {
+                    bodies.braced_statements = None;
+                } else {
+                    bodies.braced_statements = Some(statements + 1);
+                }
+            } else if bodies.awaiting_body {
+                if code.starts_with('{') {
+                    bodies.braced_statements = Some(0);
+                } else {
+                    bodies.unbraced += 1;
+                }
+                bodies.awaiting_body = false;
+            }
+
+            if let Some(body) = control_statement_body(code) {
+                if body.is_empty() {
+                    bodies.awaiting_body = true;
+                } else if body == "{" {
+                    bodies.braced_statements = Some(0);
+                } else if !body.starts_with('{') && body.ends_with(';') {
+                    bodies.unbraced += 1;
+                }
+            }
+            false
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.find("doubled_value") && !info.find("return");
+            if result {
+                let value = if info.find("((") { "Leave" } else { "MultipleParentheses" };
+                info.settings.borrow_mut().cleanup.remove_parentheses.set(value);
+            }
+            result
+        });
+
+        add_topic(topics, |info: &mut LineInfo| {
+            let result = info.find("return") && info.find("doubled_value");
+            if result && !info.find("return (") {
+                let mut settings = info.settings.borrow_mut();
+                if settings.cleanup.remove_parentheses.get_value() == Some(&"MultipleParentheses") {
+                    settings.cleanup.remove_parentheses.set("ReturnStatement");
+                }
+            }
+            result
+        });
+
+        let mut extra_semicolon = Statement::new("intextra_semicolon()", &['}']);
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if !extra_semicolon.collect(info) {
+                return info.finished;
+            }
+            let last = extra_semicolon.lines.last().map(|line| code_part(line).trim_end()).unwrap_or("");
+            info.settings.borrow_mut().cleanup.remove_semicolon.set(!last.ends_with(';'));
+            true
+        });
+
+        let mut literals = IntegerLiterals {
+            binary: Vec::new(),
+            decimal: Vec::new(),
+            hex: Vec::new(),
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let mut settings = info.settings.borrow_mut();
+                let separator = &mut settings.integer_literal_separator;
+                let groups = [
+                    (&literals.binary, &mut separator.binary, &mut separator.binary_min_digits),
+                    (&literals.decimal, &mut separator.decimal, &mut separator.decimal_min_digits),
+                    (&literals.hex, &mut separator.hex, &mut separator.hex_min_digits),
+                ];
+                for (observed, group, min_digits) in groups {
+                    if let Some((size, min)) = literal_grouping(observed) {
+                        group.set(size);
+                        if let Some(min) = min {
+                            min_digits.set(min);
+                        }
+                    }
+                }
+                return true;
+            }
+
+            if !info.line.trim_start().starts_with('#') {
+                for literal in integer_literals(code_part(&info.line)) {
+                    let digits = literal.trim_start_matches("0x").trim_start_matches("0X");
+                    let (observed, digits) = if digits.len() < literal.len() {
+                        (&mut literals.hex, digits)
+                    } else if let Some(digits) = literal.strip_prefix("0b").or(literal.strip_prefix("0B")) {
+                        (&mut literals.binary, digits)
+                    } else {
+                        (&mut literals.decimal, literal)
+                    };
+                    let count = digits.chars().filter(|c| *c != '\'').count() as u32;
+                    let group = digits.rsplit('\'').next().filter(|_| digits.contains('\''));
+                    observed.push((count, group.map(|group| group.len() as u32)));
+                }
+            }
+            false
+        });
+
+        let mut statement_indent = None;
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                return true;
+            }
+            if let Some(indent) = statement_indent {
+                if info.line.trim() == "{" {
+                    return false;
+                }
+                let width = indentation(&info.line).saturating_sub(indent);
+                info.settings.borrow_mut().braced_lists.braced_initializer_indent_width.set(width as u32);
+                return true;
+            }
+            if info.find("table_entries[]") {
+                statement_indent = Some(indentation(&info.line));
+            }
+            false
+        });
+    }
+
+    fn set_macro_topics(topics: &mut Vec<TopicInfo>) {
+        let mut usages = MacroUsages {
+            in_macro: false,
+            previous: String::new(),
+            pending: None,
+            header: None,
+            scopes: Vec::new(),
+            found: Vec::new(),
+        };
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let mut settings = info.settings.borrow_mut();
+                // macros of macro blocks are already known to clang-format
+                let preprocessor = &settings.preprocessor;
+                let blocks = [&preprocessor.macro_block_begin, &preprocessor.macro_block_end]
+                    .iter()
+                    .filter_map(|block| block.get_value().cloned())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                let block_names: Vec<&str> = blocks.split(|c: char| !(c.is_alphanumeric() || c == '_')).collect();
+                usages.found.retain(|(name, _)| !block_names.contains(&name.as_str()));
+
+                let macros = &mut settings.macros;
+                let lists = [
+                    (MacroKind::ForEach, &mut macros.for_each_macros),
+                    (MacroKind::If, &mut macros.if_macros),
+                    (MacroKind::Statement, &mut macros.statement_macros),
+                    (MacroKind::StatementAttributeLike, &mut macros.statement_attribute_like_macros),
+                    (MacroKind::Attribute, &mut macros.attribute_macros),
+                    (MacroKind::Typename, &mut macros.typename_macros),
+                    (MacroKind::WhitespaceSensitive, &mut macros.whitespace_sensitive_macros),
+                ];
+                for (kind, setting) in lists {
+                    let defaults = kind.defaults();
+                    let names: Vec<String> = usages
+                        .found
+                        .iter()
+                        .filter(|(name, found)| *found == kind && !defaults.contains(&name.as_str()))
+                        .map(|(name, _)| name.clone())
+                        .collect();
+                    if !names.is_empty() {
+                        let list = defaults.iter().map(|name| name.to_string()).chain(names).collect();
+                        setting.set(StringList(list));
+                    }
+                }
+                return true;
+            }
+
+            let line = info.line.trim();
+            if usages.in_macro || line.starts_with('#') {
+                if let Some(name) = stringizing_macro(line) {
+                    usages.add(name, MacroKind::WhitespaceSensitive);
+                }
+                usages.in_macro = line.ends_with('\\');
+                return false;
+            }
+            let code = code_part(line).trim();
+            if code.is_empty() {
+                return false;
+            }
+            let indent = indentation(&info.line);
+            // loops and conditions only appear in function bodies, 'TEST(suite, name) {' defines a function
+            let in_function = usages.scopes.iter().any(|namespace| !namespace);
+
+            // a macro on its own line is a loop header, if a body follows, or a statement otherwise
+            if let Some((name, pending_indent, after_statement)) = usages.pending.take() {
+                let body = code.starts_with('{') || (indent > pending_indent && code.ends_with(';'));
+                if body && in_function {
+                    let kind = MacroKind::header(&name);
+                    usages.header = Some((name.clone(), pending_indent));
+                    usages.add(name, kind);
+                } else if !body && indent <= pending_indent && after_statement {
+                    usages.add(name, MacroKind::Statement);
+                }
+            }
+
+            // a header, which is continued with an else, is an if macro
+            if let Some((name, header_indent)) = usages.header.clone() {
+                if indent == header_indent && code != "{" && code != "}" {
+                    if code.starts_with("else") || code.starts_with("} else") {
+                        usages.add(name, MacroKind::If);
+                    }
+                    usages.header = None;
+                }
+            }
+
+            if let Some((name, rest)) = leading_macro(code) {
+                let rest = rest.trim_start();
+                match rest.strip_prefix('(').and_then(|_| after_parentheses(rest)) {
+                    Some("") => usages.pending = Some((name.to_string(), indent, !usages.previous.ends_with(','))),
+                    Some(after) if after.starts_with('{') && in_function => {
+                        usages.header = Some((name.to_string(), indent));
+                        usages.add(name.to_string(), MacroKind::header(name));
+                    }
+                    Some(after) if after.starts_with('{') => {}
+                    Some(after) if declared_name(after) => usages.add(name.to_string(), MacroKind::Typename),
+                    Some(after) if declaration_like(after) => usages.add(name.to_string(), MacroKind::Attribute),
+                    Some(_) => {}
+                    None if rest.is_empty() => {
+                        usages.pending = Some((name.to_string(), indent, !usages.previous.ends_with(',')));
+                    }
+                    None if declaration_like(rest) => usages.add(name.to_string(), MacroKind::Attribute),
+                    None if call_like(rest) => usages.add(name.to_string(), MacroKind::StatementAttributeLike),
+                    None => {}
+                }
+            } else if let Some(rest) = code.strip_prefix("class ").or(code.strip_prefix("struct ")) {
+                if let Some((name, rest)) = leading_macro(rest.trim_start()) {
+                    if rest.trim_start().starts_with(|c: char| c.is_alphabetic() || c == '_') {
+                        usages.add(name.to_string(), MacroKind::Attribute);
+                    }
+                }
+            }
+
+            let namespace = ["namespace", "extern"].iter().any(|keyword| code.starts_with(keyword))
+                || (code.starts_with('{') && usages.previous.starts_with("namespace"));
+            for c in code.chars() {
+                match c {
+                    '{' => usages.scopes.push(namespace),
+                    '}' => {
+                        usages.scopes.pop();
+                    }
+                    _ => {}
+                }
+            }
+            usages.previous = code.to_string();
+            false
+        });
+    }
+
+    fn set_breaking_topics(topics: &mut Vec<TopicInfo>) {
+        let mut attributes = Attributes {
+            own_line: 0,
+            same_line: 0,
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                let value = match (attributes.own_line, attributes.same_line) {
+                    (0, 0) => return true,
+                    (_, 0) => "Always",
+                    (0, _) => "Never",
+                    _ => "Leave",
+                };
+                info.settings.borrow_mut().breaking.break_after_attributes.set(value);
+                return true;
+            }
+
+            // statement attributes like '[[fallthrough]];' are not followed by a declaration
+            match after_attributes(code_part(&info.line).trim()) {
+                Some("") => attributes.own_line += 1,
+                Some(rest) if !rest.starts_with(';') => attributes.same_line += 1,
+                _ => {}
+            }
+            false
+        });
+
+        let mut annotations = JavaAnnotations {
+            pending: false,
+            own_line: 0,
+            same_line: 0,
+        };
+
+        add_topic(topics, move |info: &mut LineInfo| {
+            if info.finished {
+                if annotations.own_line + annotations.same_line > 0 {
+                    let value = annotations.own_line > annotations.same_line;
+                    info.settings.borrow_mut().breaking.break_after_java_field_annotations.set(value);
+                }
+                return true;
+            }
+
+            let code = code_part(&info.line).trim();
+            match java_annotation(code) {
+                Some("") => annotations.pending = true,
+                Some(rest) => {
+                    annotations.same_line += java_field(rest) as u32;
+                    annotations.pending = false;
+                }
+                None if annotations.pending && !code.is_empty() => {
+                    annotations.own_line += java_field(code) as u32;
+                    annotations.pending = false;
+                }
+                None => {}
+            }
+            false
+        });
+
+        let mut string_literal = Statement::new("long_string_literal", &[';']);
+
+        add_structural_topic(topics, move |info: &mut LineInfo| {
+            let result = string_literal.collect(info);
+            if result {
+                // the literal was split, if it consists of more than one pair of quotes
+                let quotes: usize = string_literal.lines.iter().map(|line| line.matches('"').count()).sum();
+                info.settings.borrow_mut().breaking.break_string_literals.set(quotes > 2);
+            }
+            result || info.finished
+        });
+    }
+}
+
+impl Parser for Impl {
+    fn parse_line(&mut self, line: &str) {
+        let (excluded, exclusion) = self.exclusion.next(line);
+        self.exclusion = exclusion;
+        if excluded {
+            self.skipped = true;
+            return;
+        }
+
+        // annotated lines are read for their option and the structure of the code, not by the other topics
+        let mut annotated = false;
+        if let Some((option, code)) = cfg_annotation(line) {
+            annotated = true;
+            match self.annotation_rules.iter().position(|rule| rule.option == option) {
+                None => {
+                    let warning = format!("Unknown option '{}' in @cfg annotation.", option);
+                    self.settings.borrow_mut().warnings.push(warning);
+                }
+                Some(rule) if code_part(code).trim().is_empty() => self.annotation = Some(rule),
+                Some(rule) => self.annotated.push((rule, code.to_string())),
+            }
+        } else if let Some(rule) = self.annotation.filter(|_| !line.trim().is_empty()) {
+            // an annotation on its own line binds the next line, which isn't empty
+            annotated = true;
+            self.annotation = None;
+            self.annotated.push((rule, line.to_string()));
+        }
+
+        let mut info = LineInfo {
+            line: line.to_string(),
+            settings: self.settings.clone(),
+            finished: false,
+            region_break: std::mem::take(&mut self.skipped),
+            annotated,
+        };
+        self.run_checks(&mut info);
+    }
+
+    fn finish(&mut self) {
+        let mut info = LineInfo {
+            line: "".to_string(),
+            settings: self.settings.clone(),
+            finished: true,
+            region_break: false,
+            annotated: false,
+        };
+        self.run_checks(&mut info);
+
+        // annotations take precedence over the identifiers of the reference
+        let mut settings = self.settings.borrow_mut();
+        for (rule, line) in &self.annotated {
+            self.annotation_rules[*rule].apply(line, &mut settings);
+        }
+    }
+}
+
+// lines, which don't follow the style and are left out of the inference
+#[derive(Clone, Copy, PartialEq)]
+enum Exclusion {
+    None,
+    NextLine,
+    ClangFormatOff,
+    Region,
+}
+
+impl Exclusion {
+    // whether the line is excluded and the exclusion of the following lines
+    fn next(self, line: &str) -> (bool, Exclusion) {
+        let comment = marker_comment(line).unwrap_or_default();
+        let marker = |name: &str| is_marker(comment, name);
+        match self {
+            Exclusion::ClangFormatOff if marker("clang-format on") => (true, Exclusion::None),
+            Exclusion::Region if marker("cfg-generator: on") => (true, Exclusion::None),
+            Exclusion::ClangFormatOff | Exclusion::Region => (true, self),
+            _ if marker("clang-format off") => (true, Exclusion::ClangFormatOff),
+            _ if marker("cfg-generator: off") => (true, Exclusion::Region),
+            // a trailing marker excludes its own line, otherwise the marker excludes the next one
+            _ if marker("cfg-generator: ignore") => {
+                let trailing = !code_part(line).trim().is_empty();
+                (true, if trailing { Exclusion::None } else { Exclusion::NextLine })
+            }
+            Exclusion::NextLine => (true, Exclusion::None),
+            Exclusion::None => (false, Exclusion::None),
+        }
+    }
+}
+
+// whether the comment is the marker, like clang-format an explanation may follow behind a colon as in
+// 'clang-format off: generated table'
+fn is_marker(comment: &str, marker: &str) -> bool {
+    comment.strip_prefix(marker).is_some_and(|rest| rest.is_empty() || rest.starts_with(':'))
+}
+
+// option of an annotation like '// @cfg IndentWidth' and the line in front of the annotation, which may
+// follow another comment like in '} // namespace lib // @cfg FixNamespaceComments'
+fn cfg_annotation(line: &str) -> Option<(&str, &str)> {
+    let (start, opener) = line.match_indices("@cfg ").find_map(|(index, _)| {
+        let before = line[..index].trim_end();
+        let code = before.strip_suffix("//").or(before.strip_suffix("/*"))?;
+        Some((index, code.len()))
+    })?;
+    let option = line[start + "@cfg ".len()..].trim().trim_end_matches("*/").trim_end();
+    Some((option, line[..opener].trim_end()))
+}
+
+// text of the comment on the line, like 'clang-format off'
+fn marker_comment(line: &str) -> Option<&str> {
+    let start = [line.find("//"), line.find("/*")].into_iter().flatten().min()?;
+    Some(line[start + 2..].trim().trim_end_matches("*/").trim_end())
+}
+
+struct LineWidths {
+    widths: Vec<usize>,
+}
+
+struct Attributes {
+    own_line: u32,
+    same_line: u32,
+}
+
+struct JavaAnnotations {
+    pending: bool,
+    own_line: u32,
+    same_line: u32,
+}
+
+struct EmptyLines {
+    runs: Vec<u32>,
+    current: u32,
+    at_start_of_file: Option<bool>,
+    opens_block: bool,
+    at_start_of_block: Option<bool>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Scope {
+    Namespace,
+    Class,
+    Function,
+    Code,
+}
+
+struct DefinitionBlocks {
+    scopes: Vec<Scope>,
+    previous: String,
+    in_macro: bool,
+    ended: bool,
+    empty_line: bool,
+    separated: u32,
+    adjacent: u32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum AccessContext {
+    Start,
+    AccessModifier,
+    Comment,
+    Code,
+}
+
+struct AccessModifiers {
+    context: AccessContext,
+    empty_line: bool,
+    after_code: Vec<bool>,
+    after_comment: Option<bool>,
+}
+
+struct Qualifiers {
+    west: usize,
+    east: usize,
+    orders: Vec<Vec<&'static str>>,
+    pointers: Vec<ALIGNMENT>,
+    // whether const is west of the type on the 'qualifier_west' and 'qualifier_east' probes
+    probes: (Option<bool>, Option<bool>),
+}
+
+struct ControlBodies {
+    in_macro: bool,
+    awaiting_body: bool,
+    braced_statements: Option<u32>,
+    braced: u32,
+    unbraced: u32,
+}
+
+// digit count and group size of the observed literals
+struct IntegerLiterals {
+    binary: Vec<(u32, Option<u32>)>,
+    decimal: Vec<(u32, Option<u32>)>,
+    hex: Vec<(u32, Option<u32>)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum MacroKind {
+    ForEach,
+    If,
+    Statement,
+    StatementAttributeLike,
+    Attribute,
+    Typename,
+    WhitespaceSensitive,
+}
+
+impl MacroKind {
+    fn header(name: &str) -> Self {
+        if name.split('_').any(|part| part == "IF") {
+            MacroKind::If
+        } else {
+            MacroKind::ForEach
+        }
+    }
+
+    // lists of clang-format, which a configured list replaces
+    fn defaults(&self) -> &'static [&'static str] {
+        match self {
+            MacroKind::ForEach => &["foreach", "Q_FOREACH", "BOOST_FOREACH"],
+            MacroKind::If => &["KJ_IF_MAYBE"],
+            MacroKind::Statement => &["Q_UNUSED", "QT_REQUIRE_VERSION"],
+            MacroKind::StatementAttributeLike => &["Q_EMIT"],
+            MacroKind::Attribute => &["__capability"],
+            MacroKind::Typename => &[],
+            MacroKind::WhitespaceSensitive => {
+                &["STRINGIZE", "PP_STRINGIZE", "BOOST_PP_STRINGIZE", "NS_SWIFT_NAME", "CF_SWIFT_NAME"]
+            }
+        }
+    }
+}
+
+struct MacroUsages {
+    in_macro: bool,
+    previous: String,
+    pending: Option<(String, usize, bool)>,
+    header: Option<(String, usize)>,
+    // open braces, true for namespaces, which don't start a function body
+    scopes: Vec<bool>,
+    found: Vec<(String, MacroKind)>,
+}
+
+impl MacroUsages {
+    // an if macro is first seen as a loop header, later usages don't change the kind otherwise
+    fn add(&mut self, name: String, kind: MacroKind) {
+        match self.found.iter_mut().find(|(found, _)| *found == name) {
+            Some((_, found)) if *found == MacroKind::ForEach && kind == MacroKind::If => *found = kind,
+            Some(_) => {}
+            None => self.found.push((name, kind)),
+        }
+    }
+}
+
+struct ArrayTables {
+    rows: Vec<String>,
+    in_table: bool,
+    alignment: Option<&'static str>,
+}
+
+#[derive(Default)]
+struct SpaceBeforeParens {
+    control_statements: Option<bool>,
+    foreach_macros: Option<bool>,
+    function_declaration_name: Option<bool>,
+    function_definition_name: Option<bool>,
+    if_macros: Option<bool>,
+    overloaded_operator: Option<bool>,
+    placement_operator: Option<bool>,
+    requires_in_clause: Option<bool>,
+    requires_in_expression: Option<bool>,
+    non_empty_parentheses: Option<bool>,
+    empty_parentheses: Option<bool>,
+}
+
+impl SpaceBeforeParens {
+    // named mode of SpaceBeforeParens, which produces the same spacing
+    fn mode(&self) -> &'static str {
+        let control = self.control_statements.unwrap_or(false);
+        let control_macros = (self.foreach_macros.unwrap_or(false), self.if_macros.unwrap_or(false));
+        let others = [
+            self.function_declaration_name,
+            self.function_definition_name,
+            self.overloaded_operator,
+            self.requires_in_clause,
+            self.requires_in_expression,
+        ];
+
+        if self.empty_parentheses == Some(true) {
+            "Always"
+        } else if self.non_empty_parentheses == Some(true) {
+            "NonEmptyParentheses"
+        } else if others.contains(&Some(true)) || self.placement_operator == Some(false) {
+            "Custom"
+        } else {
+            match (control, control_macros) {
+                (false, (false, false)) => "Never",
+                (true, (true, true)) => "ControlStatements",
+                (true, (false, false)) => "ControlStatementsExceptControlMacros",
+                _ => "Custom",
+            }
+        }
+    }
+
+    // nearest mode, which all versions know
+    fn fallback(&self) -> &'static str {
+        if self.empty_parentheses == Some(true) {
+            "Always"
+        } else if self.control_statements == Some(true) {
+            "ControlStatements"
+        } else {
+            "Never"
+        }
+    }
+}
+
+struct ShortFunctions {
+    inline: Option<bool>,
+    top_level: Option<bool>,
+    empty: Option<bool>,
+}
+
+struct ReturnTypes {
+    member_declaration: Option<bool>,
+    member_definition: Option<bool>,
+    top_level_declaration: Option<bool>,
+    top_level_definition: Option<bool>,
+}
+
+struct TemplateDeclarations {
+    short_broken: Option<bool>,
+    definition_broken: Option<bool>,
+}
+
+struct RequiresExpression {
+    concept_indent: Option<usize>,
+    requires_column: Option<usize>,
+}
+
+struct ShortLambdas {
+    assigned: Option<bool>,
+    argument: Option<bool>,
+    empty: Option<bool>,
+}
+
+struct LambdaBody {
+    statement_indent: Option<usize>,
+    lambda_indent: Option<usize>,
+}
+
+struct MacroBlocks {
+    previous: Option<(String, usize)>,
+    open: Vec<(String, usize)>,
+    begin: Vec<String>,
+    end: Vec<String>,
+}
+
+struct NamespaceEnds {
+    pending: bool,
+    blocks: Vec<bool>,
+    comments: Vec<Option<&'static str>>,
+}
+
+struct BinaryOperators {
+    boolean: Statement,
+    arithmetic: Statement,
+    assignment: Statement,
+}
+
+struct WrappedOperators {
+    break_before: bool,
+    align_operands: &'static str,
+    packed: bool,
+}
+
+struct ShortIfs {
+    simple: Option<bool>,
+    with_else: Option<bool>,
+    else_branch: Option<bool>,
+}
+
+pub(crate) fn indentation(line: &str) -> usize {
+    line.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+// Collects the lines of a statement, starting at the line containing the marker
+// and ending at the first line containing one of the terminators.
+// The marker is matched against the line without whitespace.
+struct Statement {
+    marker: &'static str,
+    terminators: &'static [char],
+    lines: Vec<String>,
+    complete: bool,
+}
+
+impl Statement {
+    fn new(marker: &'static str, terminators: &'static [char]) -> Self {
+        Self {
+            marker,
+            terminators,
+            lines: Vec::new(),
+            complete: false,
+        }
+    }
+
+    // returns true once the statement is complete
+    fn collect(&mut self, info: &LineInfo) -> bool {
+        if self.complete {
+            return true;
+        }
+        // a statement doesn't continue across excluded lines
+        if info.region_break {
+            self.lines.clear();
+        }
+        if info.finished || (self.lines.is_empty() && (info.annotated || !info.compact().contains(self.marker))) {
+            return false;
+        }
+        self.lines.push(info.line.clone());
+        self.complete = info.line.contains(self.terminators);
+        self.complete
+    }
+
+    fn reset(&mut self) {
+        self.lines.clear();
+        self.complete = false;
+    }
+
+    fn contains(&self, search: &str) -> bool {
+        self.lines.iter().any(|line| line.contains(search))
+    }
+
+    // width of the statement if it was written on a single line
+    fn single_line_width(&self) -> usize {
+        let mut text = String::new();
+        for line in &self.lines {
+            let line = line.trim();
+            if !text.is_empty() && !text.ends_with('(') && !line.starts_with(')') {
+                text.push(' ');
+            }
+            text.push_str(line);
+        }
+        indentation(&self.lines[0]) + display_width(&text)
+    }
+
+    // layout of the bracketed list, if the statement had to be wrapped to fit into the column limit
+    fn wrapped_list(&self, info: &LineInfo) -> Option<WrappedList> {
+        if self.lines.len() < 2 {
+            return None;
+        }
+        if let Some(&limit) = info.settings.borrow().column_limit.get_value() {
+            if limit > 0 && self.single_line_width() <= limit as usize {
+                return None;
+            }
+        }
+
+        let first = &self.lines[0];
+        let open = first.find('(')?;
+        let last = self.lines[self.lines.len() - 1].trim_start();
+
+        let mut items_per_line: Vec<usize> = Vec::new();
+        items_per_line.push(count_items(&first[open + 1..]));
+        for line in &self.lines[1..] {
+            items_per_line.push(count_items(line));
+        }
+        let lines_with_items = items_per_line.iter().filter(|&&items| items > 0).count();
+
+        let break_after_open = first.trim_end().ends_with('(');
+        Some(WrappedList {
+            break_after_open,
+            closing_on_own_line: last.starts_with(')'),
+            aligned: indentation(&self.lines[1]) == first[..open + 1].chars().count(),
+            packed: items_per_line.iter().any(|&items| items > 1),
+            single_continuation: break_after_open && lines_with_items == 1,
+        })
+    }
+}
+
+impl Statement {
+    // layout of the constructor initializer list, if the initializers were wrapped
+    fn constructor_initializers(&self) -> Option<ConstructorInitializers> {
+        let mut lines: Vec<&str> = self.lines.iter().map(|line| line.trim_end()).collect();
+        if lines.last().is_some_and(|line| line.trim() == "{") {
+            lines.pop();
+        }
+        let first = *lines.first()?;
+        let (index, colon) = lines.iter().enumerate().find_map(|(index, line)| {
+            // the colon of the first line has to follow the parameter list
+            let start = if index == 0 { line.rfind(')')? } else { 0 };
+            find_single_colon(&line[start..]).map(|colon| (index, start + colon))
+        })?;
+
+        let colon_line = lines[index];
+        let style = if colon_line.trim_start().starts_with(':') {
+            if lines[index + 1..].iter().any(|line| line.trim_start().starts_with(',')) {
+                Some("BeforeComma")
+            } else {
+                Some("BeforeColon")
+            }
+        } else if colon_line.trim_end_matches('{').trim_end().ends_with(':') {
+            Some("AfterColon")
+        } else {
+            None
+        };
+
+        let space_before_colon = if colon_line.trim_start().starts_with(':') {
+            None
+        } else {
+            Some(colon_line[..colon].ends_with(' '))
+        };
+
+        // the colon is on the constructor line and no initializers were wrapped
+        if index == 0 && lines.len() == 1 {
+            return Some(ConstructorInitializers {
+                style: None,
+                indent_width: 0,
+                lines_with_items: 0,
+                packed: false,
+                space_before_colon,
+            });
+        }
+
+        let first_initializer = if style == Some("AfterColon") { index + 1 } else { index };
+        let indent_width = lines
+            .get(first_initializer)
+            .map(|line| indentation(line).saturating_sub(indentation(first)))
+            .unwrap_or(0);
+
+        let mut items_per_line = vec![count_items(&colon_line[colon + 1..])];
+        items_per_line.extend(lines[index + 1..].iter().map(|line| count_items(line)));
+        Some(ConstructorInitializers {
+            style,
+            indent_width,
+            lines_with_items: items_per_line.iter().filter(|&&items| items > 0).count(),
+            packed: items_per_line.iter().any(|&items| items > 1),
+            space_before_colon,
+        })
+    }
+}
+
+// loop and if macros, which clang-format knows by default
+const CONTROL_MACROS: [&str; 3] = ["Q_FOREACH", "BOOST_FOREACH", "KJ_IF_MAYBE"];
+
+// name of the macro, if the line is nothing but an upper case macro invocation
+fn macro_invocation(line: &str) -> Option<String> {
+    let line = line.trim();
+    let name_end = line.find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+        .unwrap_or(line.len());
+    let (name, rest) = line.split_at(name_end);
+    let rest = rest.trim_start();
+    let is_call = rest.is_empty() || (rest.starts_with('(') && rest.ends_with(')'));
+    let control = CONTROL_MACROS.contains(&name);
+    if name.len() > 1 && name.starts_with(|c: char| c.is_ascii_uppercase()) && is_call && !control {
+        Some(name.to_string())
+    } else {
+        None
+    }
+}
+
+fn macro_regex(names: &[String]) -> String {
+    if names.len() == 1 {
+        format!("'^{}$'", names[0])
+    } else {
+        format!("'^({})$'", names.join("|"))
+    }
+}
+
+// include with its delimiters, e.g. <vector> or "header.h"
+fn include_name(line: &str) -> Option<String> {
+    let directive = line.trim_start().strip_prefix('#')?.trim_start().strip_prefix("include")?.trim();
+    let close = match directive.chars().next()? {
+        '"' => '"',
+        '<' => '>',
+        _ => return None,
+    };
+    let end = directive[1..].find(close)?;
+    Some(directive[..end + 2].to_string())
+}
+
+// regex of the category an include belongs to
+fn include_kind(name: &str) -> String {
+    let delimiter = &name[..1];
+    let path = &name[1..name.len() - 1];
+    if let Some((directory, _)) = path.split_once('/') {
+        format!("'^{}{}/'", delimiter, directory)
+    } else if delimiter == "\"" {
+        "'^\"'".to_string()
+    } else if path.contains('.') {
+        "'^<[[:alnum:]_]+\\.[[:alnum:]]+>'".to_string()
+    } else {
+        "'^<[[:alnum:]_]+>'".to_string()
+    }
+}
+
+fn set_include_settings(groups: &[Vec<String>], settings: &mut ClangFormatSettings) {
+    let case_sensitive = groups.iter().all(|group| group.windows(2).all(|pair| pair[0] <= pair[1]));
+    let case_insensitive = groups
+        .iter()
+        .all(|group| group.windows(2).all(|pair| pair[0].to_lowercase() <= pair[1].to_lowercase()));
+    let sort = if case_sensitive {
+        "CaseSensitive"
+    } else if case_insensitive {
+        "CaseInsensitive"
+    } else {
+        "Never"
+    };
+    settings.includes.sort_includes.set(sort);
+    settings.includes.sort_includes_legacy.set(sort != "Never");
+
+    // a single quoted include on top is the main header, which clang-format always puts first
+    let main_header = groups.len() > 1 && groups[0].len() == 1 && groups[0][0].starts_with('"');
+    let groups = if main_header { &groups[1..] } else { groups };
+
+    let group_kinds: Vec<Option<String>> = groups
+        .iter()
+        .map(|group| {
+            let kind = include_kind(&group[0]);
+            group.iter().all(|name| include_kind(name) == kind).then_some(kind)
+        })
+        .collect();
+
+    let mut regexes: Vec<String> = Vec::new();
+    for (group, kind) in groups.iter().zip(&group_kinds) {
+        let regex = kind.clone().unwrap_or_else(|| {
+            if group.iter().all(|name| name.starts_with('"')) {
+                "'^\"'".to_string()
+            } else if group.iter().all(|name| name.starts_with('<')) {
+                "'^<'".to_string()
+            } else {
+                "'.*'".to_string()
+            }
+        });
+        if !regexes.contains(&regex) {
+            regexes.push(regex);
+        }
+    }
+
+    let homogeneous = group_kinds.iter().all(Option::is_some);
+    let blocks = if groups.len() > 1 && homogeneous && regexes.len() == groups.len() {
+        "Regroup"
+    } else if groups.len() == 1 && !homogeneous {
+        "Merge"
+    } else {
+        "Preserve"
+    };
+    settings.includes.include_blocks.set(blocks);
+
+    if !regexes.iter().any(|regex| regex == "'.*'") {
+        regexes.push("'.*'".to_string());
+    }
+    let categories = regexes
+        .into_iter()
+        .enumerate()
+        .map(|(index, regex)| IncludeCategory {
+            regex,
+            priority: index as u32 + 1,
+        })
+        .collect();
+    settings.includes.include_categories.set(IncludeCategories(categories));
+}
+
+// Columns of the aligned token in the first two lines of a run,
+// in the line after an empty line and in the line after a comment.
+struct AlignmentRun<const N: usize> {
+    markers: [&'static str; N],
+    columns: [Option<usize>; N],
+}
+
+impl<const N: usize> AlignmentRun<N> {
+    fn new(markers: [&'static str; N]) -> Self {
+        Self {
+            markers,
+            columns: [None; N],
+        }
+    }
+
+    fn collect<F>(&mut self, line: &str, column: F)
+    where
+        F: Fn(&str, &str) -> Option<usize>,
+    {
+        for (marker, value) in self.markers.iter().zip(self.columns.iter_mut()) {
+            if value.is_none() && line.contains(marker) {
+                *value = column(line, marker);
+            }
+        }
+    }
+
+}
+
+impl AlignmentRun<4> {
+    fn set(&self, settings: &mut AlignConsecutiveSettings) {
+        let [Some(first), Some(second), after_empty_line, after_comment] = self.columns else {
+            return;
+        };
+
+        let enabled = first == second;
+        let across_empty_lines = enabled && after_empty_line == Some(first);
+        let across_comments = enabled && after_comment.is_some() && after_comment == after_empty_line;
+
+        settings.legacy.set(enabled);
+        settings.legacy_enum.set(match (enabled, across_empty_lines, across_comments) {
+            (false, _, _) => "None",
+            (true, false, false) => "Consecutive",
+            (true, true, false) => "AcrossEmptyLines",
+            (true, false, true) => "AcrossComments",
+            (true, true, true) => "AcrossEmptyLinesAndComments",
+        });
+        settings.enabled.set(enabled);
+        settings.across_empty_lines.set(across_empty_lines);
+        settings.across_comments.set(across_comments);
+    }
+}
+
+// start of the assignment operator and position of its '='
+pub(crate) fn find_assignment(line: &str) -> Option<(usize, usize)> {
+    let bytes = line.as_bytes();
+    let equal = (0..bytes.len()).find(|&i| {
+        let previous = if i > 0 { bytes[i - 1] } else { b' ' };
+        // '<=' and '>=' are comparisons, '<<=' and '>>=' are assignments, '[=]' is a lambda capture
+        let comparison = matches!(previous, b'=' | b'!' | b'[')
+            || (matches!(previous, b'<' | b'>') && (i < 2 || bytes[i - 2] != previous));
+        bytes[i] == b'=' && bytes.get(i + 1) != Some(&b'=') && !comparison
+    })?;
+    let start = line[..equal]
+        .rfind(|c: char| !matches!(c, '+' | '-' | '*' | '/' | '%' | '&' | '|' | '^' | '<' | '>'))
+        .map_or(0, |position| position + 1);
+    Some((start, equal))
+}
+
+// line without its comment
+pub(crate) fn code_part(line: &str) -> &str {
+    let end = [line.find("//"), line.find("/*")].into_iter().flatten().min().unwrap_or(line.len());
+    &line[..end]
+}
+
+// style of the comment, which closes a namespace
+fn namespace_comment(line: &str) -> Option<&'static str> {
+    let comment = line[line.find('}')? + 1..].trim();
+    if let Some(text) = comment.strip_prefix("//") {
+        let text = text.trim_start();
+        if text.starts_with("end") && text.contains("namespace") {
+            return Some("// end namespace");
+        } else if text.contains("namespace") {
+            return Some("// namespace");
+        }
+    } else if comment.starts_with("/*") && comment.contains("namespace") {
+        return Some("/* namespace */");
+    }
+    None
+}
+
+const BINARY_OPERATORS: [&str; 8] = ["&&", "||", "+", "-", "*", "/", "%", "|"];
+
+fn starts_with_operator(text: &str) -> Option<&'static str> {
+    BINARY_OPERATORS.iter().copied().find(|operator| text.starts_with(operator))
+}
+
+fn ends_with_operator(text: &str) -> Option<&'static str> {
+    BINARY_OPERATORS.iter().copied().find(|operator| text.ends_with(operator))
+}
+
+impl Statement {
+    // layout of an assignment, whose right hand side was wrapped at binary operators
+    fn wrapped_operators(&self) -> Option<WrappedOperators> {
+        if self.lines.len() < 2 {
+            return None;
+        }
+        let first = &self.lines[0];
+        let (_, equal) = find_assignment(first)?;
+        let operand_column = equal + 1 + indentation(&first[equal + 1..]);
+
+        let second = self.lines[1].trim_start();
+        let indent = indentation(&self.lines[1]);
+        let (break_before, align_operands) = match starts_with_operator(second) {
+            Some(operator) => {
+                let operand = indent + operator.len() + indentation(&second[operator.len()..]);
+                let align = if operand == operand_column {
+                    "AlignAfterOperator"
+                } else if indent == operand_column {
+                    "Align"
+                } else {
+                    "DontAlign"
+                };
+                (true, align)
+            }
+            None => {
+                ends_with_operator(first.trim_end())?;
+                (false, if indent == operand_column { "Align" } else { "DontAlign" })
+            }
+        };
+
+        // a line with more than one operator of the expression was packed
+        let separator = if self.contains("&&") { " && " } else { " + " };
+        let packed = self.lines.iter().any(|line| {
+            let trailing = usize::from(line.trim_end().ends_with(separator.trim()));
+            line.matches(separator).count() + trailing > 1
+        });
+        Some(WrappedOperators {
+            break_before,
+            align_operands,
+            packed,
+        })
+    }
+}
+
+// qualifiers in the order, in which clang-format sorts them, if the code doesn't tell otherwise
+const QUALIFIERS: [&str; 7] = ["static", "inline", "constexpr", "const", "volatile", "restrict", "type"];
+
+const BUILTIN_TYPES: [&str; 11] =
+    ["void", "bool", "char", "short", "int", "long", "float", "double", "unsigned", "auto", "size_t"];
+
+// order of the qualifiers and the type in a declaration like 'static const int value'
+fn qualifier_order(segment: &str) -> Option<Vec<&'static str>> {
+    let mut order = Vec::new();
+    let tokens = segment.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':' || c == '*' || c == '&'));
+    for token in tokens.filter(|token| !token.is_empty()) {
+        // qualifiers behind a pointer belong to the pointer, not to the type
+        if token.starts_with(['*', '&']) {
+            break;
+        }
+        let name = token.trim_end_matches(['*', '&']);
+        if let Some(qualifier) = QUALIFIERS.iter().find(|qualifier| **qualifier == name && name != "type") {
+            order.push(*qualifier);
+        } else if matches!(name, "return" | "typename" | "class" | "struct" | "template" | "using" | "case") {
+            return None;
+        } else if !order.contains(&"type") {
+            order.push("type");
+        }
+        if name.len() < token.len() {
+            break;
+        }
+    }
+    let qualified = order.iter().any(|name| *name != "type");
+    (qualified && order.contains(&"type")).then_some(order)
+}
+
+// combined order of all observed declarations, none if they contradict each other
+fn merge_qualifier_orders(orders: &[Vec<&'static str>]) -> Option<Vec<&'static str>> {
+    let before = |first: &str, second: &str| {
+        orders.iter().any(|order| {
+            let position = |name: &str| order.iter().position(|item| *item == name);
+            matches!((position(first), position(second)), (Some(a), Some(b)) if a < b)
+        })
+    };
+
+    let mut names: Vec<&'static str> =
+        QUALIFIERS.iter().copied().filter(|name| orders.iter().any(|order| order.contains(name))).collect();
+    let mut merged = Vec::new();
+    while !names.is_empty() {
+        let index = names.iter().position(|name| !names.iter().any(|other| other != name && before(other, name)))?;
+        merged.push(names.remove(index));
+    }
+    Some(merged)
+}
+
+// true, if the qualifiers are in the order, which the Left or Right alignment produces
+fn aligned_qualifier_order(order: &[&str], alignment: &str) -> bool {
+    let position = |name: &str| {
+        let position = QUALIFIERS.iter().position(|qualifier| *qualifier == name).unwrap_or(0);
+        let east = alignment == "Right" && matches!(name, "const" | "volatile");
+        if east { position + QUALIFIERS.len() } else { position }
+    };
+    order.windows(2).all(|pair| position(pair[0]) < position(pair[1]))
+}
+
+// placement of the pointer in a declaration like 'int* value', references are left out, because
+// ReferenceAlignment may place them differently
+fn pointer_placement(segment: &str) -> Option<ALIGNMENT> {
+    let position = segment.find('*')?;
+    let (left, right) = segment.split_at(position);
+    let right = right.trim_start_matches('*');
+    let type_name = left.split_whitespace().rev().find(|token| !matches!(*token, "const" | "volatile"))?;
+    // an upper case constant like 'BUFFER_SIZE * count' is a multiplication
+    let constant = type_name.len() > 1 && !type_name.contains(|c: char| c.is_ascii_lowercase());
+    let is_type = BUILTIN_TYPES.contains(&type_name)
+        || type_name.contains("::")
+        || type_name.ends_with('>')
+        || (type_name.starts_with(|c: char| c.is_ascii_uppercase()) && !constant);
+    // the declarator is a single name, possibly behind further qualified pointers
+    let mut declarator = right
+        .split(|c: char| c.is_whitespace() || c == '*')
+        .filter(|token| !matches!(*token, "" | "const" | "volatile"));
+    let name = declarator.next().filter(|_| declarator.next().is_none())?;
+    let identifier = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !is_type || !identifier {
+        return None;
+    }
+    match (left.ends_with(' '), right.starts_with(' ')) {
+        (false, true) => Some(ALIGNMENT::LEFT),
+        (true, true) => Some(ALIGNMENT::MIDDLE),
+        (true, false) => Some(ALIGNMENT::RIGHT),
+        (false, false) => None,
+    }
+}
+
+// start and end columns of the elements of a braced row like '{1, "first", 0.5},'
+fn row_elements(row: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<char> = row.chars().collect();
+    let (Some(open), Some(close)) = (chars.iter().position(|&c| c == '{'), chars.iter().rposition(|&c| c == '}'))
+    else {
+        return Vec::new();
+    };
+
+    let mut elements = Vec::new();
+    let mut start = open + 1;
+    let mut in_string = false;
+    for i in open + 1..=close {
+        if chars[i] == '"' {
+            in_string = !in_string;
+        }
+        if i == close || (chars[i] == ',' && !in_string) {
+            let element = &chars[start..i];
+            if let Some(first) = element.iter().position(|c| !c.is_whitespace()) {
+                let last = element.iter().rposition(|c| !c.is_whitespace()).unwrap_or(first);
+                elements.push((start + first, start + last + 1));
+            }
+            start = i + 1;
+        }
+    }
+    elements
+}
+
+// AlignArrayOfStructures of the rows, if any column has elements of different widths
+fn table_alignment(rows: &[String]) -> Option<&'static str> {
+    let rows: Vec<Vec<(usize, usize)>> = rows.iter().map(|row| row_elements(row)).collect();
+    let columns = rows[0].len();
+    if columns < 2 || rows.iter().any(|row| row.len() != columns) {
+        return None;
+    }
+    for column in 0..columns {
+        let starts_aligned = rows.iter().all(|row| row[column].0 == rows[0][column].0);
+        let ends_aligned = rows.iter().all(|row| row[column].1 == rows[0][column].1);
+        match (starts_aligned, ends_aligned) {
+            (true, false) if column > 0 => return Some("Left"),
+            (false, true) => return Some("Right"),
+            (false, false) => return Some("None"),
+            _ => {}
+        }
+    }
+    None
+}
+
+// code behind the condition of an if, else, for or while statement
+fn control_statement_body(code: &str) -> Option<&str> {
+    let (keyword, rest) = ["if", "for", "while", "else", "} else"]
+        .iter()
+        .find_map(|keyword| code.strip_prefix(keyword).map(|rest| (*keyword, rest)))?;
+    if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    let rest = rest.trim_start();
+    if keyword.ends_with("else") {
+        return if rest.starts_with("if") { control_statement_body(rest) } else { Some(rest) };
+    }
+    if !rest.starts_with('(') {
+        return None;
+    }
+
+    let mut depth = 0;
+    for (index, c) in rest.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(rest[index + 1..].trim());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// integer literals of the code, without floating point numbers and suffixes
+fn integer_literals(code: &str) -> Vec<&str> {
+    let bytes = code.as_bytes();
+    let mut literals = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let previous = if i > 0 { bytes[i - 1] } else { b' ' };
+        if !bytes[i].is_ascii_digit() || previous.is_ascii_alphanumeric() || matches!(previous, b'_' | b'.' | b'\'') {
+            i += 1;
+            continue;
+        }
+        let end = (i..bytes.len())
+            .find(|&j| !(bytes[j].is_ascii_alphanumeric() || bytes[j] == b'\''))
+            .unwrap_or(bytes.len());
+        let literal = code[i..end].trim_end_matches(['u', 'U', 'l', 'L']);
+        let hex = literal.starts_with("0x") || literal.starts_with("0X");
+        let float = bytes.get(end) == Some(&b'.') || (!hex && literal.contains(['e', 'E', 'f', 'F']));
+        if !float && literal.chars().all(|c| c.is_ascii_hexdigit() || matches!(c, '\'' | 'x' | 'X' | 'b' | 'B')) {
+            literals.push(literal);
+        }
+        i = end;
+    }
+    literals
+}
+
+// group size and minimum digit count of separated literals, 0 if long literals are never separated
+fn literal_grouping(literals: &[(u32, Option<u32>)]) -> Option<(i32, Option<u32>)> {
+    let Some(size) = literals.iter().find_map(|(_, group)| *group) else {
+        return literals.iter().any(|(digits, _)| *digits > 4).then_some((0, None));
+    };
+    let longest_plain = literals
+        .iter()
+        .filter(|(digits, group)| group.is_none() && *digits > size)
+        .map(|(digits, _)| *digits)
+        .max();
+    Some((size as i32, longest_plain.map(|digits| digits + 1)))
+}
+
+// upper case name at the start of the code and the code behind it
+fn leading_macro(code: &str) -> Option<(&str, &str)> {
+    let end = code
+        .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+        .unwrap_or(code.len());
+    let (name, rest) = code.split_at(end);
+    let is_name = name.len() > 1 && name.starts_with(|c: char| c.is_ascii_uppercase());
+    (is_name && !rest.starts_with(|c: char| c.is_alphanumeric())).then_some((name, rest))
+}
+
+// code behind the parenthesized arguments at the start of the text
+fn after_parentheses(text: &str) -> Option<&str> {
+    let mut depth = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(text[index + 1..].trim());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn identifier_end(text: &str) -> usize {
+    text.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')).unwrap_or(text.len())
+}
+
+// just a name like in 'STACK_OF(int) values;'
+fn declared_name(text: &str) -> bool {
+    let end = identifier_end(text);
+    end > 0 && text[end..].trim_start().starts_with([';', '=', ',', ')'])
+}
+
+// a declaration like 'void function();' or 'Widget widget;'
+fn declaration_like(text: &str) -> bool {
+    let end = identifier_end(text);
+    let first = &text[..end];
+    // another macro like 'CALLBACK' in 'LRESULT CALLBACK WindowProc(' isn't a type
+    if leading_macro(text).is_some() {
+        return declaration_like(text[end..].trim_start());
+    }
+    let keyword = BUILTIN_TYPES.contains(&first)
+        || QUALIFIERS.contains(&first)
+        || ["class", "struct", "enum", "explicit", "virtual", "extern", "template"].contains(&first);
+    let rest = text[end..].trim_start_matches(['*', '&', ' ']);
+    keyword || (end > 0 && text[end..].starts_with([' ', '*', '&']) && identifier_end(rest) > 0)
+}
+
+// a call like 'signal(value);' or 'object->signal(value);'
+fn call_like(text: &str) -> bool {
+    let end = text.find(|c: char| !(c.is_alphanumeric() || "_:.->".contains(c)));
+    end.is_some_and(|end| end > 0 && text[end..].starts_with('('))
+}
+
+// name of a function-like macro, which turns a parameter into a string
+fn stringizing_macro(line: &str) -> Option<String> {
+    let definition = line.strip_prefix('#')?.trim_start().strip_prefix("define")?.trim_start();
+    let open = definition.find('(')?;
+    let name = &definition[..open];
+    let close = definition.find(')')?;
+    let parameters: Vec<&str> = definition[open + 1..close].split(',').map(str::trim).collect();
+    let body = &definition[close + 1..];
+    let stringized = body.match_indices('#').any(|(index, _)| {
+        let operand = &body[index + 1..];
+        let pasting = operand.starts_with('#') || body[..index].ends_with('#');
+        let parameter = &operand.trim_start()[..identifier_end(operand.trim_start())];
+        !pasting && parameters.contains(&parameter)
+    });
+    (stringized && !name.contains(char::is_whitespace)).then(|| name.to_string())
+}
+
+// clang-format's default TabWidth, the generator doesn't infer another one
+const TAB_WIDTH: usize = 8;
+
+// width of the line in columns, wide characters take two columns and tabs advance to the next tab stop
+pub(crate) fn display_width(line: &str) -> usize {
+    line.split('\t').enumerate().fold(0, |width, (index, part)| {
+        let width = if index > 0 { (width / TAB_WIDTH + 1) * TAB_WIDTH } else { width };
+        width + UnicodeWidthStr::width(part)
+    })
+}
+
+const COLUMN_LIMITS: [usize; 6] = [80, 100, 120, 132, 140, 160];
+
+// smallest common limit, which is exceeded by hardly any line, a few outliers are tolerated even in short files
+fn column_limit(widths: &[usize]) -> Option<u32> {
+    let allowed = (widths.len() / 100).max(2);
+    let exceeding = |limit: usize| widths.iter().filter(|&&width| width > limit).count();
+    // the cutoff is only sharp, if some lines come close to it
+    let sharp = |limit: usize| widths.iter().any(|&width| width <= limit && width + 10 > limit);
+    match COLUMN_LIMITS.into_iter().find(|&limit| exceeding(limit) <= allowed && sharp(limit)) {
+        Some(limit) => Some(limit as u32),
+        // lines beyond any common limit were never wrapped, if there are more than a few of them
+        None => (exceeding(160) * 20 >= widths.len() && exceeding(160) > 0).then_some(0),
+    }
+}
+
+// code behind the attributes at the start of the line like '[[nodiscard]]'
+fn after_attributes(code: &str) -> Option<&str> {
+    let mut rest = code.strip_prefix("[[")?;
+    loop {
+        rest = rest[rest.find("]]")? + 2..].trim_start();
+        match rest.strip_prefix("[[") {
+            Some(next) => rest = next,
+            None => return Some(rest),
+        }
+    }
+}
+
+// code behind a Java annotation like '@Nullable', Objective-C keywords like '@interface' are lower case
+fn java_annotation(code: &str) -> Option<&str> {
+    let name = code.strip_prefix('@').filter(|name| name.starts_with(|c: char| c.is_ascii_uppercase()))?;
+    let rest = &name[identifier_end(name)..];
+    if rest.starts_with('(') {
+        after_parentheses(rest)
+    } else {
+        Some(rest.trim())
+    }
+}
+
+// a field like 'private String name;', methods have parameters
+fn java_field(code: &str) -> bool {
+    code.ends_with(';') && !code.contains('(')
+}
+
+// largest run of empty lines, which isn't just an outlier of the file
+fn max_empty_lines(runs: &[u32]) -> u32 {
+    let threshold = if runs.len() > 1 { (runs.len() / 20).max(2) } else { 1 };
+    let mut sorted = runs.to_vec();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    sorted.get(threshold - 1).or(sorted.last()).copied().unwrap_or(0)
+}
+
+fn access_modifier(code: &str) -> bool {
+    ["public:", "protected:", "private:"].contains(&code)
+}
+
+// kind of the scope, which a brace opens after the statement
+fn scope_kind(statement: &str, enclosing: Option<Scope>) -> Scope {
+    let first_word = statement.split_whitespace().next().unwrap_or("");
+    if matches!(enclosing, Some(Scope::Function | Scope::Code)) || find_assignment(statement).is_some() {
+        Scope::Code
+    } else if first_word == "namespace" || first_word == "extern" {
+        Scope::Namespace
+    } else if ["class", "struct", "union", "enum"].contains(&first_word) {
+        Scope::Class
+    } else {
+        Scope::Function
+    }
+}
+
+// whether the name is followed by a space before its opening parenthesis
+fn space_before_parenthesis(line: &str, name: &str) -> Option<bool> {
+    let rest = &line[line.find(name)? + name.len()..];
+    if rest.starts_with(" (") {
+        Some(true)
+    } else if rest.starts_with('(') {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+// whether the single colon of a line is preceded and followed by a space
+fn colon_spacing(line: &str) -> Option<(bool, bool)> {
+    let colon = find_single_colon(line)?;
+    Some((line[..colon].ends_with(' '), line[colon + 1..].starts_with(' ')))
+}
+
+// position of a colon, which is not part of a scope operator
+fn find_single_colon(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    (0..bytes.len()).find(|&i| {
+        bytes[i] == b':' && (i == 0 || bytes[i - 1] != b':') && bytes.get(i + 1) != Some(&b':')
+    })
+}
+
+struct ConstructorInitializers {
+    style: Option<&'static str>,
+    indent_width: usize,
+    lines_with_items: usize,
+    packed: bool,
+    space_before_colon: Option<bool>,
+}
+
+struct ShortConstructor {
+    on_current_line: Option<bool>,
+    space_before_colon: Option<bool>,
+}
+
+fn count_items(text: &str) -> usize {
+    let text = text.trim_end_matches(|c: char| matches!(c, '{' | ';' | ')') || c.is_whitespace());
+    text.split(',').filter(|item| !item.trim().is_empty()).count()
+}
+
+struct WrappedList {
+    break_after_open: bool,
+    closing_on_own_line: bool,
+    aligned: bool,
+    packed: bool,
+    single_continuation: bool,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lineinfo() {
+        let settings = Rc::new(RefCell::new(ClangFormatSettings::new()));
+        let  info = LineInfo {
+            line: "Hello World".to_string(),
+            settings: settings.clone(),
+            finished: false,
+            region_break: false,
+            annotated: false,
+        };
+        assert!(info.find("World"));
+        assert!(!info.without("World"));
+    }
+
+
+    #[test]
+    fn test_add_topic() {
+        let mut topics: Vec<TopicInfo> = Vec::new();
+
+        add_topic(&mut topics, |info: &mut LineInfo| info.find("World"));
+
+        // the stored function reads the line it is called with
+        let topic = topics.first_mut().unwrap();
+        let mut line_info = LineInfo {
+            line: "Hello World".to_string(),
+            settings: Rc::new(RefCell::new(ClangFormatSettings::new())),
+            finished: false,
+            region_break: false,
+            annotated: false,
+        };
+        assert!((topic.func)(&mut line_info));
+        assert!(!topic.structural);
+    }
+
+    fn parse_lines<'a>(lines: impl IntoIterator<Item = &'a str>) -> Rc<RefCell<ClangFormatSettings>> {
+        let settings = Rc::new(RefCell::new(ClangFormatSettings::new()));
+        let mut parser = Impl::new(settings.clone(), crate::rules::builtin_rules());
+        for line in lines {
+            parser.parse_line(line);
+        }
+        parser.finish();
+        settings
+    }
+
+    #[test]
+    fn test_short_constructs_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        assert_eq!(settings.allow_short.functions_on_a_single_line.get_value(), Some(&"All"));
+        assert_eq!(settings.allow_short.if_statements_on_a_single_line.get_value(), Some(&"AllIfsAndElse"));
+        assert_eq!(settings.allow_short.loops_on_a_single_line.get_value(), Some(&true));
+        assert_eq!(settings.allow_short.blocks_on_a_single_line.get_value(), Some(&"Empty"));
+        assert_eq!(settings.allow_short.enums_on_a_single_line.get_value(), Some(&true));
+        assert_eq!(settings.allow_short.lambdas_on_a_single_line.get_value(), Some(&"All"));
+    }
+
+    #[test]
+    fn test_short_functions_inline() {
+        let settings = parse_lines([
+            "    int get_value() const { return value; }",
+            "void empty_function() {}",
+            "int short_function()",
+            "{",
+            "    return 0;",
+            "}",
+            "    if (count)",
+            "        return;",
+            "    if (count == 1) count = 0;",
+            "    else",
+            "        count = 1;",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.allow_short.functions_on_a_single_line.get_value(), Some(&"Inline"));
+        assert_eq!(settings.allow_short.if_statements_on_a_single_line.get_value(), Some(&"Never"));
+        assert_eq!(settings.allow_short.if_statements_on_a_single_line_legacy.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_signatures_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        assert_eq!(settings.signatures.align_after_open_bracket.get_value(), Some(&"Align"));
+        assert_eq!(settings.signatures.bin_pack_parameters.get_value(), Some(&true));
+        assert_eq!(settings.signatures.bin_pack_arguments.get_value(), Some(&true));
+        assert_eq!(settings.signatures.allow_all_parameters_of_declaration_on_next_line.get_value(), Some(&true));
+        assert_eq!(settings.signatures.allow_all_arguments_on_next_line.get_value(), Some(&true));
+        assert_eq!(settings.signatures.indent_wrapped_function_names.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_signatures_block_indent() {
+        let settings = parse_lines([
+            "///////////////////////////////////// MAX WIDTH //////////////////////////////",
+            "void long_function_declaration(",
+            "    int first_parameter,",
+            "    int second_parameter,",
+            "    int third_parameter",
+            ");",
+            "void function_with_a_long_name_and_parameters_that_fit_on_the_next_line(",
+            "    int first_parameter,",
+            "    int second_parameter",
+            ");",
+            "std::map<std::string, std::vector<int>>",
+            "    wrapped_function_name_after_a_long_return_type(int first_parameter);",
+            "    long_function_declaration(first_argument, second_argument, third_argument, first_argument,",
+            "        second_argument);",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.signatures.align_after_open_bracket.get_value(), Some(&"BlockIndent"));
+        assert_eq!(settings.signatures.bin_pack_parameters.get_value(), Some(&false));
+        assert_eq!(settings.signatures.bin_pack_arguments.get_value(), Some(&true));
+        assert_eq!(settings.signatures.allow_all_parameters_of_declaration_on_next_line.get_value(), Some(&false));
+        assert!(!settings.signatures.allow_all_arguments_on_next_line.is_set());
+        assert_eq!(settings.signatures.indent_wrapped_function_names.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_return_type_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        assert_eq!(settings.return_type.always_break_after_return_type.get_value(), Some(&"None"));
+        assert_eq!(settings.return_type.break_after_return_type.get_value(), Some(&"Automatic"));
+        assert_eq!(settings.return_type.always_break_after_definition_return_type.get_value(), Some(&"None"));
+    }
+
+    #[test]
+    fn test_return_type_gnu_style() {
+        let settings = parse_lines([
+            "    std::vector<int> member_declaration(int parameter);",
+            "    std::vector<int>",
+            "    member_definition(int parameter)",
+            "std::vector<int> top_level_declaration(int parameter);",
+            "std::vector<int>",
+            "top_level_definition(int parameter)",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.return_type.break_after_return_type.get_value(), Some(&"AllDefinitions"));
+        assert_eq!(settings.return_type.always_break_after_definition_return_type.get_value(), Some(&"All"));
+    }
+
+    #[test]
+    fn test_constructor_initializers_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let ctor = &settings.constructor_initializers;
+        assert_eq!(ctor.break_constructor_initializers.get_value(), Some(&"BeforeColon"));
+        assert_eq!(ctor.pack_constructor_initializers.get_value(), Some(&"NextLine"));
+        assert_eq!(ctor.all_on_one_line_or_one_per_line.get_value(), Some(&true));
+        assert_eq!(ctor.indent_width.get_value(), Some(&4));
+        assert_eq!(ctor.space_before_colon.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_constructor_initializers_before_comma() {
+        let settings = parse_lines([
+            "    ReferenceClass(int* value, float& ref)",
+            "      : pointer_member(value)",
+            "      , reference_member(ref)",
+            "      , counter_member(0)",
+            "    {",
+            "    explicit ReferenceClass(int count)",
+            "      : counter_member(count)",
+        ]);
+        let settings = settings.borrow();
+        let ctor = &settings.constructor_initializers;
+        assert_eq!(ctor.break_constructor_initializers.get_value(), Some(&"BeforeComma"));
+        assert_eq!(ctor.break_constructor_initializers_before_comma.get_value(), Some(&true));
+        assert_eq!(ctor.pack_constructor_initializers.get_value(), Some(&"Never"));
+        assert_eq!(ctor.indent_width.get_value(), Some(&2));
+        assert!(!ctor.space_before_colon.is_set());
+    }
+
+    #[test]
+    fn test_constructor_initializers_after_colon() {
+        let settings = parse_lines([
+            "    ReferenceClass(int* value, float& ref):",
+            "        pointer_member(value), reference_member(ref),",
+            "        counter_member(0) {",
+        ]);
+        let settings = settings.borrow();
+        let ctor = &settings.constructor_initializers;
+        assert_eq!(ctor.break_constructor_initializers.get_value(), Some(&"AfterColon"));
+        assert_eq!(ctor.pack_constructor_initializers.get_value(), Some(&"BinPack"));
+        assert_eq!(ctor.space_before_colon.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_inheritance_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        assert_eq!(settings.inheritance.break_inheritance_list.get_value(), Some(&"BeforeColon"));
+        assert_eq!(settings.inheritance.break_before_inheritance_comma.get_value(), Some(&false));
+        assert_eq!(settings.inheritance.space_before_inheritance_colon.get_value(), Some(&true));
+        assert_eq!(settings.break_before_braces.after_class.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_inheritance_after_comma() {
+        let settings = parse_lines([
+            "class DerivedClassWithSeveralBases: public ReferenceClass,",
+            "                                    public ShortFunctions,",
+            "                                    public ReturnTypes {",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.inheritance.break_inheritance_list.get_value(), Some(&"AfterComma"));
+        assert_eq!(settings.inheritance.space_before_inheritance_colon.get_value(), Some(&false));
+        assert_eq!(settings.break_before_braces.after_class.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_templates_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let templates = &settings.templates;
+        assert_eq!(templates.break_template_declarations.get_value(), Some(&"Yes"));
+        assert_eq!(templates.always_break_template_declarations_legacy.get_value(), Some(&true));
+        assert_eq!(templates.space_after_template_keyword.get_value(), Some(&true));
+        assert_eq!(templates.spaces_in_angles.get_value(), Some(&"Never"));
+        assert_eq!(templates.requires_clause_position.get_value(), Some(&"OwnLine"));
+        assert_eq!(templates.indent_requires_clause.get_value(), Some(&true));
+        assert_eq!(templates.requires_expression_indentation.get_value(), Some(&"OuterScope"));
+    }
+
+    #[test]
+    fn test_templates_compact() {
+        let settings = parse_lines([
+            "template< typename T >",
+            "class TemplateClass",
+            "template< typename T > T short_template_function(T value);",
+            "template< typename T >",
+            "T template_function(T value) {",
+            "template< typename T > requires Incrementable<T>",
+            "T constrained_function(T value) {",
+            "template< typename T >",
+            "concept Incrementable = requires(T value) {",
+            "                            value++;",
+        ]);
+        let settings = settings.borrow();
+        let templates = &settings.templates;
+        assert_eq!(templates.break_template_declarations.get_value(), Some(&"MultiLine"));
+        assert_eq!(templates.space_after_template_keyword.get_value(), Some(&false));
+        assert_eq!(templates.spaces_in_angles.get_value(), Some(&"Always"));
+        assert_eq!(templates.requires_clause_position.get_value(), Some(&"WithPreceding"));
+        assert!(!templates.indent_requires_clause.is_set());
+        assert_eq!(templates.requires_expression_indentation.get_value(), Some(&"Keyword"));
+    }
+
+    #[test]
+    fn test_lambdas_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        assert_eq!(settings.allow_short.lambdas_on_a_single_line.get_value(), Some(&"All"));
+        assert_eq!(settings.lambda_body_indentation.get_value(), Some(&"Signature"));
+        assert_eq!(settings.break_before_braces.before_lambda_body.get_value(), Some(&false));
+        assert_eq!(settings.space_before_parens.after_function_declaration_name.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_lambdas_outer_scope() {
+        let settings = parse_lines([
+            "    auto short_lambda = [](int x) {",
+            "        return x;",
+            "    };",
+            "    auto empty_lambda = [] () {};",
+            "    call_with_lambda([](int x) { return x; });",
+            "    std::for_each(values_with_a_long_name.begin(), values_with_a_long_name.end(),",
+            "                  [&counter, &values_with_a_long_name](int value)",
+            "                  {",
+            "        counter += value;",
+            "    });",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.allow_short.lambdas_on_a_single_line.get_value(), Some(&"Inline"));
+        assert_eq!(settings.lambda_body_indentation.get_value(), Some(&"OuterScope"));
+        assert_eq!(settings.break_before_braces.before_lambda_body.get_value(), Some(&true));
+        assert_eq!(settings.space_before_parens.after_function_declaration_name.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_preprocessor_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let preprocessor = &settings.preprocessor;
+        assert_eq!(preprocessor.indent_pp_directives.get_value(), Some(&"None"));
+        assert!(!preprocessor.pp_indent_width.is_set());
+        assert_eq!(preprocessor.align_escaped_newlines.get_value(), Some(&"Left"));
+        assert_eq!(preprocessor.macro_block_begin.get_value().map(String::as_str), Some("'^BEGIN_TABLE$'"));
+        assert_eq!(preprocessor.macro_block_end.get_value().map(String::as_str), Some("'^END_TABLE$'"));
+    }
+
+    #[test]
+    fn test_preprocessor_after_hash() {
+        let settings = parse_lines([
+            "#if defined(FEATURE_ENABLED)",
+            "#  ifdef NESTED_FEATURE",
+            "#define MULTI_LINE_MACRO(a, b) \\",
+            "    do \\",
+            "    { \\",
+            "        a = b; \\",
+            "    } while (0)",
+        ]);
+        let settings = settings.borrow();
+        let preprocessor = &settings.preprocessor;
+        assert_eq!(preprocessor.indent_pp_directives.get_value(), Some(&"AfterHash"));
+        assert_eq!(preprocessor.pp_indent_width.get_value(), Some(&2));
+        assert_eq!(preprocessor.align_escaped_newlines.get_value(), Some(&"DontAlign"));
+        assert_eq!(preprocessor.align_escaped_newlines_left.get_value(), Some(&false));
+        assert!(!preprocessor.macro_block_begin.is_set());
+    }
+
+    #[test]
+    fn test_preprocessor_single_line_macro() {
+        let settings = parse_lines(["#define MULTI_LINE_MACRO(a, b) do { a = b; } while (0)", "int x;"]);
+        let settings = settings.borrow();
+        assert!(!settings.preprocessor.align_escaped_newlines.is_set());
+    }
+
+    #[test]
+    fn test_includes_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let includes = &settings.includes;
+        assert_eq!(includes.sort_includes.get_value(), Some(&"CaseSensitive"));
+        assert_eq!(includes.include_blocks.get_value(), Some(&"Regroup"));
+        let regexes: Vec<&str> = includes.include_categories.get_value().unwrap().0.iter().map(|c| c.regex.as_str()).collect();
+        assert_eq!(regexes, ["'^\"project/'", "'^<third_party/'", "'^<[[:alnum:]_]+>'", "'.*'"]);
+    }
+
+    #[test]
+    fn test_includes_merged() {
+        let settings = parse_lines([
+            "#include \"algorithm.h\"",
+            "#include \"Widget.h\"",
+            "#include <map>",
+        ]);
+        let settings = settings.borrow();
+        let includes = &settings.includes;
+        assert_eq!(includes.sort_includes.get_value(), Some(&"CaseInsensitive"));
+        assert_eq!(includes.sort_includes_legacy.get_value(), Some(&true));
+        assert_eq!(includes.include_blocks.get_value(), Some(&"Merge"));
+        let categories = &includes.include_categories.get_value().unwrap().0;
+        assert_eq!(categories.len(), 1);
+        assert_eq!(categories[0].regex, "'.*'");
+    }
+
+    #[test]
+    fn test_align_consecutive_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let align = &settings.align_consecutive;
+        assert_eq!(align.assignments.enabled.get_value(), Some(&true));
+        assert_eq!(align.assignments.across_empty_lines.get_value(), Some(&false));
+        assert_eq!(align.assignments.across_comments.get_value(), Some(&false));
+        assert_eq!(align.assignments.align_compound.get_value(), Some(&true));
+        assert_eq!(align.assignments.pad_operators.get_value(), Some(&true));
+        assert_eq!(align.declarations.legacy_enum.get_value(), Some(&"Consecutive"));
+        assert_eq!(align.bit_fields.enabled.get_value(), Some(&true));
+        assert_eq!(align.macros.enabled.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_align_consecutive_across() {
+        let settings = parse_lines([
+            "    int assign_short              = 1;",
+            "    int assign_longer_name        = 2;",
+            "    assign_short                  >>= 3;",
+            "",
+            "    int assign_after_empty_line   = 4;",
+            "    // comment between assignments",
+            "    int assign_after_comment      = 5;",
+            "    int declared_int;",
+            "    double declared_double;",
+        ]);
+        let settings = settings.borrow();
+        let align = &settings.align_consecutive;
+        assert_eq!(align.assignments.legacy_enum.get_value(), Some(&"AcrossEmptyLinesAndComments"));
+        assert_eq!(align.assignments.align_compound.get_value(), Some(&true));
+        assert_eq!(align.assignments.pad_operators.get_value(), Some(&false));
+        assert_eq!(align.declarations.legacy.get_value(), Some(&false));
+        assert_eq!(align.declarations.across_empty_lines.get_value(), Some(&false));
+        assert!(!align.bit_fields.enabled.is_set());
+    }
+
+    #[test]
+    fn test_comments_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let comments = &settings.comments;
+        assert_eq!(settings.fix_namespace_comments.get_value(), Some(&true));
+        assert!(settings.warnings.is_empty());
+        assert_eq!(comments.align_trailing_comments_kind.get_value(), Some(&"Always"));
+        assert_eq!(comments.align_trailing_comments_over_empty_lines.get_value(), Some(&0));
+        assert_eq!(comments.spaces_before_trailing_comments.get_value(), Some(&1));
+        assert_eq!(comments.spaces_in_line_comment_prefix_minimum.get_value(), Some(&1));
+        assert_eq!(comments.spaces_in_line_comment_prefix_maximum.get_value(), Some(&1));
+        assert_eq!(comments.reflow_comments.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_comments_reflowed() {
+        let settings = parse_lines([
+            "/////////////// MAX WIDTH ////////////////////////////////////////////////////////////////////",
+            "// This comment is longer than the column limit and shows whether long comments are reflowed",
+            "// by clang-format or left as they are.",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.comments.reflow_comments.get_value(), Some(&true));
+        assert_eq!(settings.comments.reflow_comments_mode.get_value(), Some(&"Always"));
+    }
+
+    #[test]
+    fn test_comments_inconsistent() {
+        let settings = parse_lines([
+            "///////////////////////////////////// MAX WIDTH //////////////////////////////",
+            "namespace lib {",
+            "namespace detail {",
+            "namespace inner {",
+            "    comment_short(1);  //first trailing comment",
+            "    comment_longer_name(2);  //  second trailing comment",
+            "// This comment is longer than the column limit and shows whether long comments are reflowed",
+            "}",
+            "} /* namespace detail */",
+            "} // end namespace lib",
+        ]);
+        let settings = settings.borrow();
+        let comments = &settings.comments;
+        assert_eq!(settings.fix_namespace_comments.get_value(), Some(&true));
+        assert_eq!(settings.warnings.len(), 2);
+        assert_eq!(comments.align_trailing_comments.get_value(), Some(&false));
+        assert_eq!(comments.spaces_before_trailing_comments.get_value(), Some(&2));
+        assert_eq!(comments.spaces_in_line_comment_prefix_minimum.get_value(), Some(&0));
+        assert_eq!(comments.spaces_in_line_comment_prefix_maximum.get_value(), Some(&-1));
+        assert_eq!(comments.reflow_comments_mode.get_value(), Some(&"Never"));
+    }
+
+    #[test]
+    fn test_operators_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let operators = &settings.operators;
+        assert_eq!(operators.break_before_binary_operators.get_value(), Some(&"None"));
+        assert_eq!(operators.break_before_ternary_operators.get_value(), Some(&true));
+        assert_eq!(operators.align_operands.get_value(), Some(&"Align"));
+        assert_eq!(operators.break_binary_operations.get_value(), Some(&"Never"));
+        assert_eq!(settings.space_before.space_before_assignment_operators.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_operators_break_before() {
+        let settings = parse_lines([
+            "    if (value == 0 || other<=1) {}",
+            "    int x= 1;",
+            "    bool long_boolean_expression = first_condition && second_condition",
+            "                                && first_condition",
+            "                                && second_condition;",
+            "    int long_arithmetic_expression = first_operand * second_operand",
+            "        + first_operand * second_operand",
+            "        + first_operand * second_operand;",
+            "    int long_ternary_expression = first_condition ?",
+            "        first_operand : second_operand;",
+            "    int result_of_a_long_assignment_statement",
+            "        = compute_a_value_with_a_rather_long_function_name(first_operand);",
+        ]);
+        let settings = settings.borrow();
+        let operators = &settings.operators;
+        assert_eq!(operators.break_before_binary_operators.get_value(), Some(&"All"));
+        assert_eq!(operators.break_before_ternary_operators.get_value(), Some(&false));
+        assert_eq!(operators.align_operands.get_value(), Some(&"AlignAfterOperator"));
+        assert_eq!(operators.break_binary_operations.get_value(), Some(&"RespectPrecedence"));
+        assert_eq!(settings.space_before.space_before_assignment_operators.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_operators_one_per_line() {
+        let settings = parse_lines([
+            "    int long_arithmetic_expression = first_operand *",
+            "                                     second_operand +",
+            "                                     first_operand *",
+            "                                     second_operand;",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.operators.break_binary_operations.get_value(), Some(&"OnePerLine"));
+    }
+
+    #[test]
+    fn test_spacing_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let spacing = &settings.spacing;
+        assert_eq!(spacing.space_after_c_style_cast.get_value(), Some(&false));
+        assert_eq!(spacing.space_after_logical_not.get_value(), Some(&false));
+        assert_eq!(spacing.space_in_empty_block.get_value(), Some(&false));
+        assert_eq!(spacing.space_around_pointer_qualifiers.get_value(), Some(&"Default"));
+        assert_eq!(spacing.bit_field_colon_spacing.get_value(), Some(&"Both"));
+        assert_eq!(settings.space_before.space_before_cpp11_braced_list.get_value(), Some(&false));
+        assert_eq!(settings.space_before.space_before_range_based_for_loop_colon.get_value(), Some(&true));
+        assert_eq!(settings.space_before.space_before_case_colon.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_spacing_variants() {
+        let settings = parse_lines([
+            "    int *value = nullptr;",
+            "    unsigned int single_bit: 1;",
+            "void empty_function()",
+            "{",
+            "}",
+            "    while (count > 2) { }",
+            "    double cast_value = (double) count;",
+            "    bool negated_value = ! count;",
+            "    std::vector<int> braced_list {1, 2, 3};",
+            "    void * const *qualified_pointer = nullptr;",
+            "    for (int value: values)",
+            "    case 1 :",
+        ]);
+        let settings = settings.borrow();
+        let spacing = &settings.spacing;
+        assert_eq!(spacing.space_after_c_style_cast.get_value(), Some(&true));
+        assert_eq!(spacing.space_after_logical_not.get_value(), Some(&true));
+        assert_eq!(spacing.space_in_empty_block.get_value(), Some(&true));
+        assert_eq!(spacing.space_around_pointer_qualifiers.get_value(), Some(&"Before"));
+        assert_eq!(spacing.bit_field_colon_spacing.get_value(), Some(&"After"));
+        assert_eq!(settings.space_before.space_before_cpp11_braced_list.get_value(), Some(&true));
+        assert_eq!(settings.space_before.space_before_range_based_for_loop_colon.get_value(), Some(&false));
+        assert_eq!(settings.space_before.space_before_case_colon.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_parentheses_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let space_before = &settings.space_before_parens;
+        assert_eq!(space_before.space_before_parens.get_value(), Some(&"ControlStatements"));
+        assert_eq!(space_before.after_foreach_macros.get_value(), Some(&true));
+        assert_eq!(space_before.after_placement_operator.get_value(), Some(&true));
+        assert_eq!(space_before.after_requires_in_clause.get_value(), Some(&false));
+        assert_eq!(settings.spaces_in_parens.spaces_in_parens.get_value(), Some(&"Never"));
+        assert_eq!(settings.spaces_in_parens.in_c_style_casts.get_value(), Some(&false));
+        assert_eq!(settings.spaces_in_parens.except_double_parentheses.get_value(), None);
+    }
+
+    #[test]
+    fn test_parentheses_custom() {
+        let settings = parse_lines([
+            "    if( value )",
+            "std::vector<int> top_level_declaration ( int parameter );",
+            "    bool operator== ( const OperatorClass& other ) const;",
+            "__attribute__((noreturn)) void no_return_function( );",
+            "    double cast_value = ( double )count;",
+            "    BOOST_FOREACH( int value, values )",
+            "        call_with_arguments( value );",
+            "    KJ_IF_MAYBE( pointer, values )",
+            "        call_without_arguments( );",
+            "    int* placement_value = new( buffer ) int( 5 );",
+        ]);
+        let settings = settings.borrow();
+        let space_before = &settings.space_before_parens;
+        assert_eq!(space_before.space_before_parens.get_value(), Some(&"Custom"));
+        assert_eq!(space_before.space_before_parens_fallback.get_value(), Some(&"Never"));
+        assert_eq!(space_before.after_control_statements.get_value(), Some(&false));
+        assert_eq!(space_before.after_function_declaration_name.get_value(), Some(&true));
+        assert_eq!(space_before.after_overloaded_operator.get_value(), Some(&true));
+        assert_eq!(space_before.after_placement_operator.get_value(), Some(&false));
+        let parens = &settings.spaces_in_parens;
+        assert_eq!(parens.spaces_in_parens.get_value(), Some(&"Custom"));
+        assert_eq!(parens.in_conditional_statements.get_value(), Some(&true));
+        assert_eq!(parens.in_c_style_casts.get_value(), Some(&true));
+        assert_eq!(parens.in_empty_parentheses.get_value(), Some(&true));
+        assert_eq!(parens.other.get_value(), Some(&true));
+        assert_eq!(parens.except_double_parentheses.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_parentheses_non_empty() {
+        let settings = parse_lines([
+            "    if (value)",
+            "    BOOST_FOREACH (int value, values)",
+            "        call_with_arguments (value);",
+            "        call_without_arguments();",
+        ]);
+        let settings = settings.borrow();
+        let space_before = &settings.space_before_parens;
+        assert_eq!(space_before.space_before_parens.get_value(), Some(&"NonEmptyParentheses"));
+        assert_eq!(space_before.space_before_parens_fallback.get_value(), Some(&"ControlStatements"));
+    }
+
+    #[test]
+    fn test_braced_lists_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let braced_lists = &settings.braced_lists;
+        assert_eq!(braced_lists.cpp11_braced_list_style.get_value(), Some(&true));
+        assert_eq!(braced_lists.align_array_of_structures.get_value(), Some(&"None"));
+    }
+
+    #[test]
+    fn test_braced_lists_aligned_tables() {
+        let settings = parse_lines([
+            "    std::vector<int> braced_list{ 1, 2, 3 };",
+            "TableEntry table_entries[] = {",
+            "    {  1,  \"first\",   0.5},",
+            "    { 20, \"second\", 12.25},",
+            "    {300,  \"third\",   7.0}",
+            "};",
+        ]);
+        let settings = settings.borrow();
+        let braced_lists = &settings.braced_lists;
+        assert_eq!(braced_lists.cpp11_braced_list_style.get_value(), Some(&false));
+        assert_eq!(braced_lists.align_array_of_structures.get_value(), Some(&"Right"));
+
+        let settings = parse_lines([
+            "TableEntry table_entries[] =",
+            "{",
+            "    {1,   \"first\",  0.5  },",
+            "    {20,  \"second\", 12.25},",
+            "};",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.braced_lists.align_array_of_structures.get_value(), Some(&"Left"));
+    }
+
+    #[test]
+    fn test_qualifiers_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let qualifiers = &settings.qualifiers;
+        assert_eq!(qualifiers.qualifier_alignment.get_value(), Some(&"Leave"));
+        assert!(!qualifiers.qualifier_order.is_set());
+        assert_eq!(settings.alignment.derive_pointer_alignment.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_qualifiers_west_const() {
+        let settings = parse_lines([
+            "const int a = 1;",
+            "void f(const std::string& s, const int* p);",
+            "static const int qualifier_west = 3;",
+            "static const int qualifier_east = 3;",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.qualifiers.qualifier_alignment.get_value(), Some(&"Left"));
+        assert!(!settings.qualifiers.qualifier_order.is_set());
+
+        let settings = parse_lines(["const int a = 1;", "void f(const std::string& s, const int* p);"]);
+        assert_eq!(settings.borrow().qualifiers.qualifier_alignment.get_value(), Some(&"Left"));
+
+        let settings =
+            parse_lines(["const int qualifier_west = 3;", "const int qualifier_east = 3;", "int const b;"]);
+        assert_eq!(settings.borrow().qualifiers.qualifier_alignment.get_value(), Some(&"Left"));
+
+        let settings = parse_lines(["int const qualifier_west = 3;", "int const qualifier_east = 3;"]);
+        assert_eq!(settings.borrow().qualifiers.qualifier_alignment.get_value(), Some(&"Right"));
+    }
+
+    #[test]
+    fn test_qualifiers_custom_order() {
+        let settings = parse_lines([
+            "constexpr static const int qualifier_constant = 1;",
+            "    int mask = FLAG_MASK & value;",
+            "    int size = BUFFER_SIZE * count;",
+            "void* const* qualified_pointer = nullptr;",
+            "void qualifier_parameters(const std::string &qualifier_reference, const int* qualifier_pointer);",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.qualifiers.qualifier_alignment.get_value(), Some(&"Custom"));
+        let order = settings.qualifiers.qualifier_order.get_value().map(|order| order.0.join(" "));
+        assert_eq!(order.as_deref(), Some("constexpr static const type"));
+        assert_eq!(settings.alignment.derive_pointer_alignment.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_qualifiers_east_const() {
+        let settings = parse_lines([
+            "int const qualifier_constant = 1;",
+            "void qualifier_parameters(std::string const &qualifier_reference, int const *qualifier_pointer);",
+            "char* const name = nullptr;",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.qualifiers.qualifier_alignment.get_value(), Some(&"Right"));
+        assert_eq!(settings.qualifiers.qualifier_order.get_value().map(|order| order.0.len()), None);
+        assert_eq!(settings.alignment.derive_pointer_alignment.get_value(), Some(&true));
+
+        let settings = parse_lines(["const int west_value = 1;", "int const east_value = 2;", "static int value = 3;"]);
+        assert_eq!(settings.borrow().qualifiers.qualifier_alignment.get_value(), Some(&"Leave"));
+    }
+
+    #[test]
+    fn test_empty_lines_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let empty_lines = &settings.empty_lines;
+        assert_eq!(settings.max_empty_lines_to_keep.get_value(), Some(&1));
+        assert_eq!(empty_lines.at_start_of_file.get_value(), Some(&true));
+        assert_eq!(empty_lines.at_start_of_block.get_value(), Some(&true));
+        assert_eq!(empty_lines.at_end_of_file.get_value(), Some(&true));
+        assert_eq!(empty_lines.separate_definition_blocks.get_value(), Some(&"Leave"));
+        assert_eq!(empty_lines.empty_line_before_access_modifier.get_value(), Some(&"Never"));
+    }
+
+    #[test]
+    fn test_empty_lines_distribution() {
+        let settings = parse_lines([
+            "class AccessModifiers",
+            "{",
+            "public:",
+            "    int first() { return 1; }",
+            "",
+            "    int second() { return 2; }",
+            "",
+            "protected:",
+            "    int protected_member;",
+            "    // comment before an access modifier",
+            "private:",
+            "    int private_member;",
+            "};",
+            "",
+            "",
+            "",
+            "void empty_line_at_start_of_block()",
+            "{",
+            "    int value = 0;",
+            "}",
+            "",
+            "void other_function() {}",
+            "",
+            "",
+            "int last_value = 0;",
+        ]);
+        let settings = settings.borrow();
+        let empty_lines = &settings.empty_lines;
+        assert_eq!(settings.max_empty_lines_to_keep.get_value(), Some(&2));
+        assert_eq!(empty_lines.at_start_of_file.get_value(), Some(&false));
+        assert_eq!(empty_lines.at_start_of_block.get_value(), Some(&false));
+        assert_eq!(empty_lines.at_end_of_file.get_value(), Some(&false));
+        assert_eq!(empty_lines.separate_definition_blocks.get_value(), Some(&"Always"));
+        assert_eq!(empty_lines.empty_line_before_access_modifier.get_value(), Some(&"LogicalBlock"));
+    }
+
+    #[test]
+    fn test_cleanup_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let cleanup = &settings.cleanup;
+        assert_eq!(cleanup.insert_braces.get_value(), Some(&false));
+        assert_eq!(cleanup.remove_braces_llvm.get_value(), Some(&false));
+        assert_eq!(cleanup.remove_parentheses.get_value(), Some(&"Leave"));
+        assert_eq!(cleanup.remove_semicolon.get_value(), Some(&false));
+        let separator = &settings.integer_literal_separator;
+        assert_eq!(separator.decimal.get_value(), Some(&0));
+        assert_eq!(separator.hex.get_value(), Some(&0));
+        assert_eq!(separator.binary.get_value(), Some(&0));
+        assert_eq!(separator.decimal_min_digits.get_value(), None);
+        assert_eq!(settings.braced_lists.braced_initializer_indent_width.get_value(), Some(&4));
+    }
+
+    #[test]
+    fn test_cleanup_variants() {
+        let settings = parse_lines([
+            "    if (count) {",
+            "        return;",
+            "    }",
+            "    for (int value : values)",
+            "    {",
+            "        count += value;",
+            "    }",
+            "    } else {",
+            "        count = 1;",
+            "    }",
+            "    int doubled_value = (value * 2);",
+            "    return doubled_value;",
+            "int extra_semicolon() { return 1; }",
+            "    int short_decimal = 1000;",
+            "    int long_decimal = 1'000'000;",
+            "    int long_hex = 0xFF'FF'FF'FF;",
+            "    double fraction = 12.25e3;",
+        ]);
+        let settings = settings.borrow();
+        let cleanup = &settings.cleanup;
+        assert_eq!(cleanup.insert_braces.get_value(), Some(&true));
+        assert_eq!(cleanup.remove_braces_llvm.get_value(), Some(&false));
+        assert_eq!(cleanup.remove_parentheses.get_value(), Some(&"ReturnStatement"));
+        assert_eq!(cleanup.remove_semicolon.get_value(), Some(&true));
+        let separator = &settings.integer_literal_separator;
+        assert_eq!(separator.decimal.get_value(), Some(&3));
+        assert_eq!(separator.decimal_min_digits.get_value(), Some(&5));
+        assert_eq!(separator.hex.get_value(), Some(&2));
+        assert_eq!(separator.hex_min_digits.get_value(), None);
+        assert_eq!(separator.binary.get_value(), None);
+
+        let settings = parse_lines(["    if (count) return;", "    while (count)", "        count--;"]);
+        assert_eq!(settings.borrow().cleanup.remove_braces_llvm.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_macros_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        let macros = &settings.macros;
+        let list = |setting: &crate::clang_format_lib::Setting<StringList>| {
+            setting.get_value().map(|list| list.0.join(" "))
+        };
+        assert_eq!(list(&macros.for_each_macros).as_deref(), Some("foreach Q_FOREACH BOOST_FOREACH FOREACH_ITEM"));
+        assert_eq!(list(&macros.if_macros).as_deref(), Some("KJ_IF_MAYBE IF_VALID"));
+        assert_eq!(
+            list(&macros.statement_macros).as_deref(),
+            Some("Q_UNUSED QT_REQUIRE_VERSION TABLE_ENTRY Q_OBJECT LOG_STATEMENT")
+        );
+        assert_eq!(list(&macros.statement_attribute_like_macros), None);
+        assert_eq!(list(&macros.attribute_macros).as_deref(), Some("__capability EXPORT_API"));
+        assert_eq!(list(&macros.typename_macros).as_deref(), Some("STACK_OF"));
+        assert!(list(&macros.whitespace_sensitive_macros).is_some_and(|list| list.ends_with("STRINGIZE_TEXT")));
+    }
+
+    #[test]
+    fn test_macros_usage_shapes() {
+        let settings = parse_lines([
+            "#define TO_TEXT(a, b) # b",
+            "#define CONCAT(a, b) a##b",
+            "void emitting()",
+            "{",
+            "    FOR_RANGE(index, 10)",
+            "        total += index;",
+            "    CHECK_IF(total > 10) {",
+            "        DO_SOMETHING()",
+            "    } else {",
+            "        SIGNAL_EMIT object->changed(total);",
+            "    }",
+            "    NO_EXPORT std::string name = \"name\";",
+            "}",
+        ]);
+        let settings = settings.borrow();
+        let macros = &settings.macros;
+        let last = |setting: &crate::clang_format_lib::Setting<StringList>| {
+            setting.get_value().and_then(|list| list.0.last().cloned())
+        };
+        assert_eq!(last(&macros.for_each_macros).as_deref(), Some("FOR_RANGE"));
+        assert_eq!(last(&macros.if_macros).as_deref(), Some("CHECK_IF"));
+        assert_eq!(last(&macros.statement_macros).as_deref(), Some("DO_SOMETHING"));
+        assert_eq!(last(&macros.statement_attribute_like_macros).as_deref(), Some("SIGNAL_EMIT"));
+        assert_eq!(last(&macros.attribute_macros).as_deref(), Some("NO_EXPORT"));
+        assert_eq!(last(&macros.whitespace_sensitive_macros).as_deref(), Some("TO_TEXT"));
+        assert_eq!(last(&macros.typename_macros), None);
+    }
+
+    #[test]
+    fn test_macros_top_level_definitions() {
+        let settings = parse_lines([
+            "namespace lib",
+            "{",
+            "TEST(Suite, Name) {",
+            "    EXPECT_TRUE(value);",
+            "}",
+            "TEST_F(Fixture, Name)",
+            "{",
+            "}",
+            "LRESULT CALLBACK WindowProc(HWND hwnd, UINT message);",
+            "} // namespace lib",
+        ]);
+        let settings = settings.borrow();
+        let macros = &settings.macros;
+        assert!(!macros.for_each_macros.is_set());
+        assert!(!macros.if_macros.is_set());
+        assert!(!macros.attribute_macros.is_set());
+    }
+
+    #[test]
+    fn test_breaking_reference() {
+        let settings = parse_lines(crate::generator::REF_CONTENT.lines());
+        let settings = settings.borrow();
+        assert_eq!(settings.templates.break_before_concept_declarations.get_value(), Some(&"Always"));
+        assert_eq!(settings.templates.break_before_concept_declarations_legacy.get_value(), Some(&true));
+        let breaking = &settings.breaking;
+        assert_eq!(breaking.break_after_attributes.get_value(), Some(&"Leave"));
+        assert_eq!(breaking.break_after_java_field_annotations.get_value(), None);
+        assert_eq!(breaking.break_string_literals.get_value(), Some(&false));
+    }
+
+    #[test]
+    fn test_breaking_variants() {
+        let settings = parse_lines([
+            "template <typename T> concept Incrementable = requires(T value) { value++; };",
+            "[[nodiscard]] int attributed_function();",
+            "[[deprecated]] [[nodiscard]] int deprecated_function();",
+            "        [[fallthrough]];",
+            "@Nullable",
+            "private String name;",
+            "@SuppressWarnings(\"unused\")",
+            "private int count;",
+            "@Override public String toString() {",
+            "const char* long_string_literal = \"This string literal is longer than the column limit, it shows \"",
+            "                                  \"whether long literals are split\";",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.templates.break_before_concept_declarations.get_value(), Some(&"Never"));
+        assert_eq!(settings.templates.break_before_concept_declarations_legacy.get_value(), Some(&false));
+        let breaking = &settings.breaking;
+        assert_eq!(breaking.break_after_attributes.get_value(), Some(&"Never"));
+        assert_eq!(breaking.break_after_java_field_annotations.get_value(), Some(&true));
+        assert_eq!(breaking.break_string_literals.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_column_limit_display_width() {
+        let banner = format!("// Größe {} MAX WIDTH", "/".repeat(21));
+        let settings = parse_lines([banner.as_str()]);
+        assert_eq!(settings.borrow().column_limit.get_value(), Some(&40));
+    }
+
+    #[test]
+    fn test_column_limit_distribution() {
+        let wrapped = format!("    int value = {};", "1 + ".repeat(20));
+        let literal = format!("    log(\"{}\");", "x".repeat(150));
+        let off = format!("    int table[] = {{{}}};", "1, ".repeat(50));
+        let settings = parse_lines([
+            wrapped.as_str(),
+            "    int other = 1;",
+            literal.as_str(),
+            "// clang-format off",
+            off.as_str(),
+            "// clang-format on",
+        ]);
+        assert_eq!(settings.borrow().column_limit.get_value(), Some(&100));
+
+        let unwrapped = format!("    int value = {};", "1 + ".repeat(50));
+        let settings = parse_lines([unwrapped.as_str(), "    int other = 1;"]);
+        assert_eq!(settings.borrow().column_limit.get_value(), Some(&0));
+
+        let settings = parse_lines(["    int value = 1;", "    int other = 1;"]);
+        assert_eq!(settings.borrow().column_limit.get_value(), None);
+
+        // a single generated line and a long comment don't decide the limit of a file
+        let generated = format!("    int value = {};", "1 + ".repeat(50));
+        let mut lines = vec![generated, format!("// {}", "text ".repeat(40))];
+        lines.extend((0..30).map(|index| format!("\tint value_{index} = {};", "1 + ".repeat(13))));
+        let settings = parse_lines(lines.iter().map(String::as_str));
+        assert_eq!(settings.borrow().column_limit.get_value(), Some(&80));
+    }
+
+    #[test]
+    fn test_excluded_lines() {
+        let settings = parse_lines([
+            "// clang-format off",
+            "int* first_pointer;",
+            "// clang-format on",
+            "/* cfg-generator: off */",
+            "int* second_pointer;",
+            "/* cfg-generator: on */",
+            "// cfg-generator: ignore",
+            "int* third_pointer;",
+            "int* fourth_pointer; // cfg-generator: ignore",
+            "int *fifth_pointer;",
+        ]);
+        assert!(matches!(settings.borrow().alignment.pointer_alignment.get_value(), Some(ALIGNMENT::RIGHT)));
+
+        // the empty lines around a region don't merge into one run
+        let settings = parse_lines([
+            "int first_value = 1;",
+            "",
+            "// clang-format off",
+            "int  table[] = {1,2};",
+            "// clang-format on",
+            "",
+            "int second_value = 2;",
+        ]);
+        assert_eq!(settings.borrow().max_empty_lines_to_keep.get_value(), Some(&1));
+        assert!(is_marker("clang-format off: generated table", "clang-format off"));
+        assert!(!is_marker("cfg-generator: ignore-file", "cfg-generator: ignore"));
+        assert!(!is_marker("clang-format offset", "clang-format off"));
+    }
+
+    #[test]
+    fn test_cfg_annotations() {
+        let settings = parse_lines([
+            "// @cfg IndentWidth",
+            "  int* member_value;",
+            "// @cfg BraceWrapping.AfterFunction",
+            "void my_function() {",
+            "int *pointer_value; // @cfg PointerAlignment",
+            "int* other_value;",
+            "// @cfg UnknownOption",
+            "// @cfg BraceWrapping.AfterNamespace",
+            "",
+            "namespace lib {",
+            "} // namespace lib // @cfg FixNamespaceComments",
+            "    bool negated = ! value; // @cfg SpaceAfterLogicalNot",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.indent_width.get_value(), Some(&2));
+        assert_eq!(settings.break_before_braces.after_namespace.get_value(), Some(&false));
+        assert_eq!(settings.fix_namespace_comments.get_value(), Some(&true));
+        assert_eq!(settings.spacing.space_after_logical_not.get_value(), Some(&true));
+        assert_eq!(settings.break_before_braces.after_function.get_value(), Some(&false));
+        assert!(matches!(settings.alignment.pointer_alignment.get_value(), Some(ALIGNMENT::RIGHT)));
+        assert_eq!(settings.warnings, ["Unknown option 'UnknownOption' in @cfg annotation."]);
+        let annotation = cfg_annotation("} // namespace lib // @cfg FixNamespaceComments");
+        assert_eq!(annotation, Some(("FixNamespaceComments", "} // namespace lib")));
+    }
+
+    #[test]
+    fn test_cfg_annotations_keep_structure() {
+        let lines = ["int a;", "", "int b; // @cfg IndentWidth", "", "int c;"];
+        let settings = parse_lines(lines);
+        assert_eq!(settings.borrow().max_empty_lines_to_keep.get_value(), Some(&1));
+
+        let settings = parse_lines([
+            "namespace lib { // @cfg BraceWrapping.AfterNamespace",
+            "int value;",
+            "} // namespace lib",
+        ]);
+        let settings = settings.borrow();
+        assert_eq!(settings.break_before_braces.after_namespace.get_value(), Some(&false));
+        assert_eq!(settings.fix_namespace_comments.get_value(), Some(&true));
+    }
+
+    #[test]
+    fn test_user_rules_take_precedence() {
+        let user = crate::rules::parse_rules(
+            "[[rule]]\noption = \"BreakStringLiterals\"\ncompact = \"long_string_literal\"\nvalue = true\n",
+        )
+        .unwrap();
+        let settings = Rc::new(RefCell::new(ClangFormatSettings::new()));
+        let rules = crate::rules::merge_rules(crate::rules::builtin_rules(), user);
+        let mut parser = Impl::new(settings.clone(), rules);
+        for line in crate::generator::REF_CONTENT.lines() {
+            parser.parse_line(line);
         }
+        parser.finish();
+        assert_eq!(settings.borrow().breaking.break_string_literals.get_value(), Some(&true));
     }
 }
@@ -0,0 +1,402 @@
+// Declarative inference rules, the built-in ones are read from rules.toml
+use crate::clang_format_lib::{
+    ClangFormatSettings, SettingAlignment, SettingNumber, SettingSwitch, SettingText, ALIGNMENT,
+};
+use crate::parser::{code_part, display_width, find_assignment, indentation};
+use serde::Deserialize;
+
+const BUILTIN_RULES: &str = include_str!("rules.toml");
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    #[serde(default)]
+    rule: Vec<RuleSpec>,
+}
+
+// a rule as it is written in the rule file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    option: String,
+    contains: Option<String>,
+    compact: Option<String>,
+    #[serde(default)]
+    excludes: Vec<String>,
+    #[serde(default)]
+    excludes_prefix: Vec<String>,
+    extract: Option<String>,
+    value: Option<toml::Value>,
+    #[serde(default)]
+    scope: RuleScope,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum RuleScope {
+    #[default]
+    First,
+    Last,
+    Annotation,
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    Switch(fn(&mut ClangFormatSettings) -> &mut SettingSwitch),
+    Number(fn(&mut ClangFormatSettings) -> &mut SettingNumber),
+    // the texts, which the option accepts
+    Text(fn(&mut ClangFormatSettings) -> &mut SettingText, &'static [&'static str]),
+    Alignment(fn(&mut ClangFormatSettings) -> &mut SettingAlignment),
+}
+
+#[derive(Clone, Copy)]
+enum Value {
+    Switch(bool),
+    Number(u32),
+    Text(&'static str),
+    Alignment(ALIGNMENT),
+}
+
+#[derive(Clone)]
+enum Extractor {
+    Indentation,
+    Width,
+    With(String),
+    Without(String),
+    SpaceBefore(String),
+    SpaceAfter(String),
+    Alignment(char),
+    AssignmentSpacing,
+}
+
+#[derive(Clone)]
+enum Source {
+    Extract(Extractor),
+    Fixed(Value),
+}
+
+#[derive(Clone)]
+pub(crate) struct Rule {
+    pub(crate) option: String,
+    pub(crate) scope: RuleScope,
+    pub(crate) user: bool,
+    contains: Option<String>,
+    compact: Option<String>,
+    excludes: Vec<String>,
+    excludes_prefix: Vec<String>,
+    target: Target,
+    source: Source,
+}
+
+impl Rule {
+    fn new(spec: RuleSpec) -> Result<Self, String> {
+        let target = target(&spec.option).ok_or_else(|| format!("Unknown option '{}'.", spec.option))?;
+        let source = match (spec.extract, spec.value) {
+            (Some(extract), None) => {
+                let extractor = extractor(&extract).ok_or_else(|| format!("Unknown extractor '{}'.", extract))?;
+                Source::Extract(extractor)
+            }
+            (None, Some(value)) => {
+                let value = fixed_value(value, &target)
+                    .ok_or_else(|| format!("Invalid value for option '{}'.", spec.option))?;
+                Source::Fixed(value)
+            }
+            _ => return Err(format!("Rule for '{}' needs either an extractor or a value.", spec.option)),
+        };
+        if let Source::Extract(extractor) = &source {
+            if !extractor.fits(&target) {
+                return Err(format!("The extractor doesn't fit the type of option '{}'.", spec.option));
+            }
+        }
+        Ok(Self {
+            option: spec.option,
+            scope: spec.scope,
+            user: false,
+            contains: spec.contains,
+            compact: spec.compact,
+            excludes: spec.excludes,
+            excludes_prefix: spec.excludes_prefix,
+            target,
+            source,
+        })
+    }
+
+    pub(crate) fn matches(&self, line: &str) -> bool {
+        let compact: String = line.split_whitespace().collect();
+        self.contains.as_ref().is_none_or(|text| line.contains(text.as_str()))
+            && self.compact.as_ref().is_none_or(|text| compact.contains(text.as_str()))
+            && !self.excludes.iter().any(|text| line.contains(text.as_str()))
+            && !self.excludes_prefix.iter().any(|text| line.trim_start().starts_with(text.as_str()))
+    }
+
+    // sets the option to the value of the line, returns whether the line had a value
+    pub(crate) fn apply(&self, line: &str, settings: &mut ClangFormatSettings) -> bool {
+        let value = match &self.source {
+            Source::Extract(extractor) => extractor.extract(line),
+            Source::Fixed(value) => Some(*value),
+        };
+        let Some(value) = value else {
+            return false;
+        };
+        match (self.target, value) {
+            (Target::Switch(setting), Value::Switch(value)) => setting(settings).set(value),
+            (Target::Number(setting), Value::Number(value)) => setting(settings).set(value),
+            (Target::Text(setting, _), Value::Text(value)) => setting(settings).set(value),
+            (Target::Alignment(setting), Value::Alignment(value)) => setting(settings).set(value),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Extractor {
+    fn extract(&self, line: &str) -> Option<Value> {
+        let value = match self {
+            Extractor::Indentation => Value::Number(indentation(line) as u32),
+            Extractor::Width => Value::Number(display_width(line) as u32),
+            Extractor::With(text) => Value::Switch(line.contains(text.as_str())),
+            Extractor::Without(text) => Value::Switch(!line.contains(text.as_str())),
+            Extractor::SpaceBefore(text) => {
+                let position = line.find(text.as_str())?;
+                Value::Switch(line[..position].ends_with(' '))
+            }
+            Extractor::SpaceAfter(text) => {
+                let position = line.find(text.as_str())?;
+                Value::Switch(line[position + text.len()..].starts_with(' '))
+            }
+            Extractor::Alignment(symbol) => {
+                let code = code_part(line);
+                let position = code.find(*symbol)?;
+                let before = code[..position].ends_with(' ');
+                let after = code[position + symbol.len_utf8()..].starts_with(' ');
+                match (before, after) {
+                    (false, true) => Value::Alignment(ALIGNMENT::LEFT),
+                    (true, true) => Value::Alignment(ALIGNMENT::MIDDLE),
+                    (true, false) => Value::Alignment(ALIGNMENT::RIGHT),
+                    (false, false) => return None,
+                }
+            }
+            Extractor::AssignmentSpacing => {
+                if line.trim_start().starts_with('#') {
+                    return None;
+                }
+                let code = code_part(line);
+                let (start, _) = find_assignment(code)?;
+                Value::Switch(code[..start].ends_with(' '))
+            }
+        };
+        Some(value)
+    }
+
+    fn fits(&self, target: &Target) -> bool {
+        match self {
+            Extractor::Indentation | Extractor::Width => matches!(target, Target::Number(_)),
+            Extractor::Alignment(_) => matches!(target, Target::Alignment(_)),
+            _ => matches!(target, Target::Switch(_)),
+        }
+    }
+}
+
+fn extractor(spec: &str) -> Option<Extractor> {
+    let (name, argument) = spec.split_once(':').unwrap_or((spec, ""));
+    let extractor = match name {
+        "indentation" => Extractor::Indentation,
+        "width" => Extractor::Width,
+        "assignment_spacing" => Extractor::AssignmentSpacing,
+        _ if argument.is_empty() => return None,
+        "with" => Extractor::With(argument.to_string()),
+        "without" => Extractor::Without(argument.to_string()),
+        "space_before" => Extractor::SpaceBefore(argument.to_string()),
+        "space_after" => Extractor::SpaceAfter(argument.to_string()),
+        "alignment" => Extractor::Alignment(argument.chars().next()?),
+        _ => return None,
+    };
+    Some(extractor)
+}
+
+fn fixed_value(value: toml::Value, target: &Target) -> Option<Value> {
+    match (target, value) {
+        (Target::Switch(_), toml::Value::Boolean(value)) => Some(Value::Switch(value)),
+        (Target::Number(_), toml::Value::Integer(value)) => u32::try_from(value).ok().map(Value::Number),
+        // the settings hold static texts, so the value is taken from the accepted ones
+        (Target::Text(_, accepted), toml::Value::String(value)) => {
+            accepted.iter().find(|text| **text == value).map(|text| Value::Text(text))
+        }
+        (Target::Alignment(_), toml::Value::String(value)) => match value.as_str() {
+            "Left" => Some(Value::Alignment(ALIGNMENT::LEFT)),
+            "Middle" => Some(Value::Alignment(ALIGNMENT::MIDDLE)),
+            "Right" => Some(Value::Alignment(ALIGNMENT::RIGHT)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// options, which rules can infer
+fn target(option: &str) -> Option<Target> {
+    let target = match option {
+        "IndentWidth" => Target::Number(|settings| &mut settings.indent_width),
+        "ColumnLimit" => Target::Number(|settings| &mut settings.column_limit),
+        "MaxEmptyLinesToKeep" => Target::Number(|settings| &mut settings.max_empty_lines_to_keep),
+        "SpacesBeforeTrailingComments" => {
+            Target::Number(|settings| &mut settings.comments.spaces_before_trailing_comments)
+        }
+        "UseTab" => Target::Text(
+            |settings| &mut settings.use_tab,
+            &["Never", "ForIndentation", "ForContinuationAndIndentation", "AlignWithSpaces", "Always"],
+        ),
+        "FixNamespaceComments" => Target::Switch(|settings| &mut settings.fix_namespace_comments),
+        "PointerAlignment" => Target::Alignment(|settings| &mut settings.alignment.pointer_alignment),
+        "ReferenceAlignment" => Target::Alignment(|settings| &mut settings.alignment.reference_alignment),
+        "DerivePointerAlignment" => Target::Switch(|settings| &mut settings.alignment.derive_pointer_alignment),
+        "BraceWrapping.AfterClass" => Target::Switch(|settings| &mut settings.break_before_braces.after_class),
+        "BraceWrapping.AfterNamespace" => {
+            Target::Switch(|settings| &mut settings.break_before_braces.after_namespace)
+        }
+        "BraceWrapping.AfterStruct" => Target::Switch(|settings| &mut settings.break_before_braces.after_struct),
+        "BraceWrapping.AfterFunction" => {
+            Target::Switch(|settings| &mut settings.break_before_braces.after_function)
+        }
+        "BraceWrapping.AfterControlStatement" => {
+            Target::Switch(|settings| &mut settings.break_before_braces.after_control_statement)
+        }
+        "BraceWrapping.AfterEnum" => Target::Switch(|settings| &mut settings.break_before_braces.after_enum),
+        "BraceWrapping.BeforeElse" => Target::Switch(|settings| &mut settings.break_before_braces.before_else),
+        "BraceWrapping.BeforeLambdaBody" => {
+            Target::Switch(|settings| &mut settings.break_before_braces.before_lambda_body)
+        }
+        "SpacesInSquareBrackets" => Target::Switch(|settings| &mut settings.spaces_in_square_brackets),
+        "SpaceBeforeSquareBrackets" => {
+            Target::Switch(|settings| &mut settings.space_before.space_before_square_brackets)
+        }
+        "SpaceBeforeAssignmentOperators" => {
+            Target::Switch(|settings| &mut settings.space_before.space_before_assignment_operators)
+        }
+        "SpaceBeforeCpp11BracedList" => {
+            Target::Switch(|settings| &mut settings.space_before.space_before_cpp11_braced_list)
+        }
+        "SpaceBeforeCaseColon" => Target::Switch(|settings| &mut settings.space_before.space_before_case_colon),
+        "SpaceAfterCStyleCast" => Target::Switch(|settings| &mut settings.spacing.space_after_c_style_cast),
+        "SpaceAfterLogicalNot" => Target::Switch(|settings| &mut settings.spacing.space_after_logical_not),
+        "SpaceInEmptyBlock" => Target::Switch(|settings| &mut settings.spacing.space_in_empty_block),
+        "SpacesInParensOptions.InConditionalStatements" => {
+            Target::Switch(|settings| &mut settings.spaces_in_parens.in_conditional_statements)
+        }
+        "SpacesInParensOptions.Other" => Target::Switch(|settings| &mut settings.spaces_in_parens.other),
+        "SpacesInConditionalStatement" => {
+            Target::Switch(|settings| &mut settings.spaces_in_parens.spaces_in_conditional_statement)
+        }
+        "SpacesInParentheses" => Target::Switch(|settings| &mut settings.spaces_in_parens.spaces_in_parentheses),
+        "AllowShortFunctionsOnASingleLine" => {
+            Target::Text(
+                |settings| &mut settings.allow_short.functions_on_a_single_line,
+                &["None", "InlineOnly", "Empty", "Inline", "All"],
+            )
+        }
+        "AllowShortIfStatementsOnASingleLine" => {
+            Target::Text(
+                |settings| &mut settings.allow_short.if_statements_on_a_single_line,
+                &["Never", "WithoutElse", "OnlyFirstIf", "AllIfsAndElse"],
+            )
+        }
+        "AllowShortLoopsOnASingleLine" => {
+            Target::Switch(|settings| &mut settings.allow_short.loops_on_a_single_line)
+        }
+        "AllowShortEnumsOnASingleLine" => {
+            Target::Switch(|settings| &mut settings.allow_short.enums_on_a_single_line)
+        }
+        "LambdaBodyIndentation" => {
+            Target::Text(|settings| &mut settings.lambda_body_indentation, &["Signature", "OuterScope"])
+        }
+        "InsertBraces" => Target::Switch(|settings| &mut settings.cleanup.insert_braces),
+        "RemoveSemicolon" => Target::Switch(|settings| &mut settings.cleanup.remove_semicolon),
+        "InsertNewlineAtEOF" => Target::Switch(|settings| &mut settings.empty_lines.insert_newline_at_eof),
+        "SeparateDefinitionBlocks" => Target::Text(
+            |settings| &mut settings.empty_lines.separate_definition_blocks,
+            &["Leave", "Always", "Never"],
+        ),
+        "BreakAfterAttributes" => {
+            Target::Text(|settings| &mut settings.breaking.break_after_attributes, &["Always", "Leave", "Never"])
+        }
+        "BreakStringLiterals" => Target::Switch(|settings| &mut settings.breaking.break_string_literals),
+        _ => return None,
+    };
+    Some(target)
+}
+
+pub(crate) fn parse_rules(text: &str) -> Result<Vec<Rule>, String> {
+    let file: RuleFile = toml::from_str(text).map_err(|err| err.message().to_string())?;
+    file.rule.into_iter().map(Rule::new).collect()
+}
+
+pub(crate) fn builtin_rules() -> Vec<Rule> {
+    parse_rules(BUILTIN_RULES).expect("the built-in rules are valid")
+}
+
+// the rules of the user replace the built-in rules of their options
+pub(crate) fn merge_rules(builtin: Vec<Rule>, user: Vec<Rule>) -> Vec<Rule> {
+    let replaced = |rule: &Rule| {
+        let annotation = rule.scope == RuleScope::Annotation;
+        user.iter().any(|other| other.option == rule.option && (other.scope == RuleScope::Annotation) == annotation)
+    };
+    let mut rules: Vec<Rule> = builtin.into_iter().filter(|rule| !replaced(rule)).collect();
+    rules.extend(user.into_iter().map(|rule| Rule { user: true, ..rule }));
+    rules
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_rules() {
+        let rules = builtin_rules();
+        assert!(rules.iter().any(|rule| rule.option == "IndentWidth" && rule.scope == RuleScope::First));
+        assert!(rules.iter().any(|rule| rule.option == "IndentWidth" && rule.scope == RuleScope::Annotation));
+        let namespace = rules.iter().find(|rule| rule.option == "BraceWrapping.AfterNamespace").unwrap();
+        assert!(namespace.matches("namespace inner { struct Empty {}; }"));
+        assert!(!namespace.matches("} // namespace inner"));
+    }
+
+    #[test]
+    fn test_user_rules() {
+        let user = parse_rules(
+            r#"
+            [[rule]]
+            option = "IndentWidth"
+            contains = "MEMBER"
+            extract = "indentation"
+
+            [[rule]]
+            option = "UseTab"
+            contains = "\t"
+            value = "Always"
+            scope = "last"
+            "#,
+        )
+        .unwrap();
+        let rules = merge_rules(builtin_rules(), user);
+        let indent_rules: Vec<&Rule> =
+            rules.iter().filter(|rule| rule.option == "IndentWidth" && rule.scope == RuleScope::First).collect();
+        assert_eq!(indent_rules.len(), 1);
+        assert!(indent_rules[0].user);
+        assert!(indent_rules[0].matches("  int MEMBER;"));
+
+        let mut settings = ClangFormatSettings::new();
+        assert!(indent_rules[0].apply("  int MEMBER;", &mut settings));
+        assert_eq!(settings.indent_width.get_value(), Some(&2));
+        let tab_rule = rules.iter().find(|rule| rule.option == "UseTab").unwrap();
+        assert!(tab_rule.apply("\tint value;", &mut settings));
+        assert_eq!(settings.use_tab.get_value(), Some(&"Always"));
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(parse_rules("[[rule]]\noption = \"Unknown\"\nextract = \"width\"").is_err());
+        assert!(parse_rules("[[rule]]\noption = \"IndentWidth\"\nextract = \"with:x\"").is_err());
+        assert!(parse_rules("[[rule]]\noption = \"IndentWidth\"\nvalue = -1").is_err());
+        assert!(parse_rules("[[rule]]\noption = \"IndentWidth\"").is_err());
+        assert!(parse_rules("[[rule]]\noption = \"UseTab\"\nvalue = \"Sometimes\"").is_err());
+    }
+}
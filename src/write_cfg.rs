@@ -1,6 +1,8 @@
 use std::{collections::HashMap, fmt};
 use std::fmt::Write;
-use crate::clang_format_lib::{VERSION, ALIGNMENT, Setting, ClangFormatSettings};
+use crate::clang_format_lib::{
+    AlignConsecutiveSettings, ClangFormatSettings, IncludeCategories, Setting, StringList, ALIGNMENT, VERSION,
+};
 
 // Function to convert VERSION to unsigned int
 fn version_to_uint(v: &VERSION) -> u32 {
@@ -8,19 +10,30 @@ fn version_to_uint(v: &VERSION) -> u32 {
         (VERSION::V3_5, 35),
         (VERSION::V3_7, 37),
         (VERSION::V3_8, 38),
+        (VERSION::V3_9, 39),
+        (VERSION::V4_0, 40),
         (VERSION::V5_0, 50),
+        (VERSION::V6_0, 60),
+        (VERSION::V7_0, 70),
+        (VERSION::V8_0, 80),
+        (VERSION::V9_0, 90),
         (VERSION::V10_0, 100),
+        (VERSION::V11_0, 110),
+        (VERSION::V12_0, 120),
         (VERSION::V13_0, 130),
         (VERSION::V14_0, 140),
+        (VERSION::V15_0, 150),
         (VERSION::V16_0, 160),
         (VERSION::V17_0, 170),
         (VERSION::V18_0, 180),
+        (VERSION::V19_0, 190),
+        (VERSION::V20_0, 200),
     ]
     .into_iter()
     .collect();
 
     // Use the get method to find the version and return 999 if not found
-    *map_v_uint.get(&v).unwrap_or(&999)
+    *map_v_uint.get(v).unwrap_or(&999)
 }
 
 fn in_version(version : u32, introduces: &VERSION) -> bool {
@@ -61,6 +74,25 @@ impl fmt::Display for ALIGNMENT {
     }
 }
 
+// Flow style list, the writer uses the block style
+impl fmt::Display for IncludeCategories {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let categories: Vec<String> = self
+            .0
+            .iter()
+            .map(|category| format!("{{ Regex: {}, Priority: {} }}", category.regex, category.priority))
+            .collect();
+        write!(f, "[{}]", categories.join(", "))
+    }
+}
+
+// Flow style list of plain scalars
+impl fmt::Display for StringList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", self.0.join(", "))
+    }
+}
+
 // Generic function to convert values to strings
 // fn value_to_string<T: fmt::Display>(arg: T) -> String {
 //     arg.to_string()
@@ -113,7 +145,7 @@ impl<'a> Writer<'a> {
             }
 
             write!(&mut oss, "{}: ", s.command).unwrap();
-            write!(&mut oss, "{}", s.get_value().unwrap().to_string()).unwrap();
+            write!(&mut oss, "{}", s.get_value().unwrap()).unwrap();
         } else {
             write!(&mut oss, "# {}: ?", s.command).unwrap();
         }
@@ -121,9 +153,43 @@ impl<'a> Writer<'a> {
         self.lines.push(oss);
     }
 
+    fn write_include_categories(&mut self, s: &Setting<IncludeCategories>) {
+        if !self.in_version(s) {
+            return;
+        }
+
+        match s.get_value() {
+            Some(categories) => {
+                self.lines.push(format!("{}:", s.command));
+                for category in &categories.0 {
+                    self.lines.push(format!("  - Regex: {}", category.regex));
+                    self.lines.push(format!("    Priority: {}", category.priority));
+                }
+            }
+            None => self.lines.push(format!("# {}: ?", s.command)),
+        }
+    }
+
+    fn write_align_consecutive(&mut self, s: &AlignConsecutiveSettings, operators: bool) {
+        if in_version_range(self.version, &VERSION::V3_8, &VERSION::V13_0) {
+            self.write(&s.legacy, false);
+        } else if in_version_range(self.version, &VERSION::V13_0, &VERSION::V15_0) {
+            self.write(&s.legacy_enum, false);
+        } else if in_version(self.version, &VERSION::V15_0) {
+            self.lines.push(format!("{}:", s.legacy.command));
+            self.write(&s.enabled, true);
+            self.write(&s.across_empty_lines, true);
+            self.write(&s.across_comments, true);
+            if operators {
+                self.write(&s.align_compound, true);
+                self.write(&s.pad_operators, true);
+            }
+        }
+    }
+
     fn in_version<VALUE>(&self, s: &Setting<VALUE>) -> bool where
     VALUE: std::fmt::Display + ToString {
-        return in_version(self.version, &s.version)
+        in_version(self.version, &s.version)
     }
 
 
@@ -141,6 +207,14 @@ pub fn write_clang_format_file(settings: &ClangFormatSettings, version: u32, lin
     writer.write(&settings.column_limit, false);
     writer.write(&settings.max_empty_lines_to_keep, false);
     writer.write(&settings.fix_namespace_comments, false);
+
+    // LineEnding replaced DeriveLineEnding and UseCRLF in 16
+    if in_version_range(version, &VERSION::V10_0, &VERSION::V16_0) {
+        writer.write(&settings.line_endings.derive_line_ending, false);
+        writer.write(&settings.line_endings.use_crlf, false);
+    } else {
+        writer.write(&settings.line_endings.line_ending, false);
+    }
     writer.new_line();
 
     if in_version(version, &VERSION::V3_8) {
@@ -154,54 +228,397 @@ pub fn write_clang_format_file(settings: &ClangFormatSettings, version: u32, lin
         writer.write(&settings.break_before_braces.after_control_statement, true);
         writer.write(&settings.break_before_braces.after_enum, true);
         writer.write(&settings.break_before_braces.before_else, true);
+        writer.write(&settings.break_before_braces.before_lambda_body, true);
     }
 
     writer.new_line();
 
-    // Adjust version checking logic as necessary
-    if in_version_range(version, &VERSION::V3_7, &VERSION::V10_0) {
-        writer.write(&settings.spaces_in_parens.spaces_in_parentheses, false);
-    } else if in_version_range(version, &VERSION::V10_0, &VERSION::V17_0) {
-        writer.write(&settings.spaces_in_parens.spaces_in_conditional_statement, false);
+    // SpacesInParens, the single switches were replaced by one structure in 17
+    let spaces_in_parens = &settings.spaces_in_parens;
+    if in_version_range(version, &VERSION::V3_7, &VERSION::V17_0) {
+        writer.write(&spaces_in_parens.spaces_in_parentheses, false);
+        writer.write(&spaces_in_parens.spaces_in_conditional_statement, false);
+        writer.write(&spaces_in_parens.spaces_in_c_style_cast_parentheses, false);
+        writer.write(&spaces_in_parens.space_in_empty_parentheses, false);
     } else if in_version(version, &VERSION::V17_0) {
-        writer.write_text("SpacesInParens: Custom");
-        writer.write_text("SpacesInParensOptions:");
+        writer.write(&spaces_in_parens.spaces_in_parens, false);
+
+        if spaces_in_parens.spaces_in_parens.get_value() == Some(&"Custom") {
+            writer.write_text("SpacesInParensOptions:");
 
-        writer.write(&settings.spaces_in_parens.in_conditional_statements, true);
-        writer.write(&settings.spaces_in_parens.other, true);
+            writer.write(&spaces_in_parens.in_conditional_statements, true);
+            writer.write(&spaces_in_parens.in_c_style_casts, true);
+            writer.write(&spaces_in_parens.in_empty_parentheses, true);
+            writer.write(&spaces_in_parens.other, true);
+            writer.write(&spaces_in_parens.except_double_parentheses, true);
+        }
     }
 
     writer.new_line();
 
     writer.write(&settings.space_before.space_before_assignment_operators, false);
-    writer.write(&settings.spaces_in_parens.spaces_in_parentheses, false);
     writer.write(&settings.space_before.space_before_square_brackets, false);
+    writer.write(&settings.space_before.space_before_cpp11_braced_list, false);
+    writer.write(&settings.space_before.space_before_range_based_for_loop_colon, false);
+    writer.write(&settings.space_before.space_before_case_colon, false);
+
+    writer.write(&settings.spacing.space_after_c_style_cast, false);
+    writer.write(&settings.spacing.space_after_logical_not, false);
+    writer.write(&settings.spacing.space_in_empty_block, false);
+    writer.write(&settings.spacing.space_around_pointer_qualifiers, false);
+    writer.write(&settings.spacing.bit_field_colon_spacing, false);
 
     // Alignment
     writer.write(&settings.alignment.pointer_alignment, false);
+    writer.write(&settings.alignment.derive_pointer_alignment, false);
 
-    if writer.in_version(&settings.alignment.reference_alignment){
-        if settings.alignment.pointer_alignment.is_set() && settings.alignment.reference_alignment.is_set() {
-            if settings.alignment.pointer_alignment.get_value() == settings.alignment.reference_alignment.get_value() {
-                writer.write_text("ReferenceAlignment: Pointer");
+    if writer.in_version(&settings.alignment.reference_alignment)
+        && settings.alignment.pointer_alignment.is_set()
+        && settings.alignment.reference_alignment.is_set()
+    {
+        if settings.alignment.pointer_alignment.get_value() == settings.alignment.reference_alignment.get_value() {
+            writer.write_text("ReferenceAlignment: Pointer");
+        } else {
+            writer.write(&settings.alignment.reference_alignment, false);
+        }
+    }
 
-            }else{
-                writer.write(&settings.alignment.reference_alignment, false);
+    writer.new_line();
+
+    // SpaceBeforeParens, older versions get the nearest mode they know
+    let space_before_parens = &settings.space_before_parens;
+    let introduced = match space_before_parens.space_before_parens.get_value() {
+        Some(&"Custom") => VERSION::V14_0,
+        Some(&"ControlStatementsExceptControlMacros") => VERSION::V11_0,
+        Some(&"NonEmptyParentheses") => VERSION::V9_0,
+        _ => VERSION::V3_5,
+    };
+    if !in_version(version, &introduced) {
+        writer.write(&space_before_parens.space_before_parens_fallback, false);
+    } else {
+        writer.write(&space_before_parens.space_before_parens, false);
+
+        if space_before_parens.space_before_parens.get_value() == Some(&"Custom") {
+            writer.write_text("SpaceBeforeParensOptions:");
+
+            writer.write(&space_before_parens.after_control_statements, true);
+            writer.write(&space_before_parens.after_foreach_macros, true);
+            writer.write(&space_before_parens.after_function_declaration_name, true);
+            writer.write(&space_before_parens.after_function_definition_name, true);
+            writer.write(&space_before_parens.after_if_macros, true);
+            writer.write(&space_before_parens.after_overloaded_operator, true);
+            writer.write(&space_before_parens.after_placement_operator, true);
+            writer.write(&space_before_parens.after_requires_in_clause, true);
+            writer.write(&space_before_parens.after_requires_in_expression, true);
+            writer.write(&space_before_parens.before_non_empty_parentheses, true);
+        }
+    }
 
+    writer.new_line();
+
+    // Short constructs on a single line
+    writer.write(&settings.allow_short.functions_on_a_single_line, false);
+
+    if in_version_range(version, &VERSION::V3_5, &VERSION::V9_0) {
+        writer.write(&settings.allow_short.if_statements_on_a_single_line_legacy, false);
+    } else if in_version_range(version, &VERSION::V9_0, &VERSION::V13_0) {
+        // OnlyFirstIf and AllIfsAndElse were introduced in 13, before that both were "Always"
+        match settings.allow_short.if_statements_on_a_single_line.get_value() {
+            Some(&"OnlyFirstIf") | Some(&"AllIfsAndElse") => {
+                writer.write_text("AllowShortIfStatementsOnASingleLine: Always")
             }
+            _ => writer.write(&settings.allow_short.if_statements_on_a_single_line, false),
+        }
+    } else {
+        writer.write(&settings.allow_short.if_statements_on_a_single_line, false);
+    }
+
+    writer.write(&settings.allow_short.loops_on_a_single_line, false);
+
+    if in_version_range(version, &VERSION::V3_5, &VERSION::V10_0) {
+        writer.write(&settings.allow_short.blocks_on_a_single_line_legacy, false);
+    } else {
+        writer.write(&settings.allow_short.blocks_on_a_single_line, false);
+    }
+
+    writer.write(&settings.allow_short.enums_on_a_single_line, false);
+    writer.write(&settings.allow_short.lambdas_on_a_single_line, false);
+    writer.write(&settings.lambda_body_indentation, false);
+
+    writer.new_line();
+
+    // Function signatures and calls
+    if in_version_range(version, &VERSION::V3_8, &VERSION::V14_0) {
+        // BlockIndent was introduced in 14, AlwaysBreak is the closest match before that
+        match settings.signatures.align_after_open_bracket.get_value() {
+            Some(&"BlockIndent") => writer.write_text("AlignAfterOpenBracket: AlwaysBreak"),
+            _ => writer.write(&settings.signatures.align_after_open_bracket, false),
+        }
+    } else {
+        writer.write(&settings.signatures.align_after_open_bracket, false);
+    }
+
+    if in_version(version, &VERSION::V20_0) && settings.signatures.bin_pack_parameters.is_set() {
+        // BinPackParameters became an enum in 20
+        match settings.signatures.bin_pack_parameters.get_value() {
+            Some(true) => writer.write_text("BinPackParameters: BinPack"),
+            _ => writer.write_text("BinPackParameters: OnePerLine"),
+        }
+    } else {
+        writer.write(&settings.signatures.bin_pack_parameters, false);
+    }
+
+    writer.write(&settings.signatures.bin_pack_arguments, false);
+    writer.write(&settings.signatures.allow_all_parameters_of_declaration_on_next_line, false);
+    writer.write(&settings.signatures.allow_all_arguments_on_next_line, false);
+    writer.write(&settings.signatures.indent_wrapped_function_names, false);
+
+    writer.new_line();
+
+    // Return types, AlwaysBreakAfterDefinitionReturnType was replaced by AlwaysBreakAfterReturnType in 3.8,
+    // which was renamed to BreakAfterReturnType in 19
+    if in_version_range(version, &VERSION::V3_7, &VERSION::V3_8) {
+        writer.write(&settings.return_type.always_break_after_definition_return_type, false);
+    } else if in_version_range(version, &VERSION::V3_8, &VERSION::V19_0) {
+        writer.write(&settings.return_type.always_break_after_return_type, false);
+    } else {
+        writer.write(&settings.return_type.break_after_return_type, false);
+    }
+
+    writer.new_line();
+
+    // Constructor initializers
+    if in_version_range(version, &VERSION::V3_7, &VERSION::V5_0) {
+        writer.write(&settings.constructor_initializers.break_constructor_initializers_before_comma, false);
+    } else {
+        writer.write(&settings.constructor_initializers.break_constructor_initializers, false);
+    }
+
+    if in_version_range(version, &VERSION::V3_7, &VERSION::V14_0) {
+        writer.write(&settings.constructor_initializers.all_on_one_line_or_one_per_line, false);
+    } else {
+        writer.write(&settings.constructor_initializers.pack_constructor_initializers, false);
+    }
+
+    writer.write(&settings.constructor_initializers.indent_width, false);
+    writer.write(&settings.constructor_initializers.space_before_colon, false);
+
+    writer.new_line();
+
+    // Inheritance list, BreakBeforeInheritanceComma was replaced by BreakInheritanceList in 7
+    if in_version_range(version, &VERSION::V5_0, &VERSION::V7_0) {
+        writer.write(&settings.inheritance.break_before_inheritance_comma, false);
+    } else if in_version_range(version, &VERSION::V7_0, &VERSION::V13_0) {
+        // AfterComma was introduced in 13
+        match settings.inheritance.break_inheritance_list.get_value() {
+            Some(&"AfterComma") => writer.write_text("BreakInheritanceList: AfterColon"),
+            _ => writer.write(&settings.inheritance.break_inheritance_list, false),
         }
+    } else {
+        writer.write(&settings.inheritance.break_inheritance_list, false);
     }
+    writer.write(&settings.inheritance.space_before_inheritance_colon, false);
 
     writer.new_line();
 
-    // SpaceBeforeParens
+    // Templates, AlwaysBreakTemplateDeclarations was an option until 7 and renamed in 19
+    if in_version_range(version, &VERSION::V3_5, &VERSION::V7_0) {
+        writer.write(&settings.templates.always_break_template_declarations_legacy, false);
+    } else if in_version_range(version, &VERSION::V7_0, &VERSION::V19_0) {
+        writer.write(&settings.templates.always_break_template_declarations, false);
+    } else {
+        writer.write(&settings.templates.break_template_declarations, false);
+    }
+    writer.write(&settings.templates.space_after_template_keyword, false);
+
     if in_version_range(version, &VERSION::V3_5, &VERSION::V14_0) {
-        writer.write(&settings.space_before.space_before_assignment_operators, false);
-    } else if in_version(version, &VERSION::V14_0) {
-        writer.write_text("SpaceBeforeParens: Custom");
-        writer.write_text("SpaceBeforeParensOptions:");
+        writer.write(&settings.templates.spaces_in_angles_legacy, false);
+    } else {
+        writer.write(&settings.templates.spaces_in_angles, false);
+    }
+
+    // Concepts, IndentRequires was renamed to IndentRequiresClause in 15
+    // and BreakBeforeConceptDeclarations became an enum
+    writer.write(&settings.templates.requires_clause_position, false);
+    if in_version_range(version, &VERSION::V13_0, &VERSION::V15_0) {
+        writer.write(&settings.templates.indent_requires, false);
+    } else {
+        writer.write(&settings.templates.indent_requires_clause, false);
+    }
+    writer.write(&settings.templates.requires_expression_indentation, false);
+    if in_version_range(version, &VERSION::V12_0, &VERSION::V15_0) {
+        writer.write(&settings.templates.break_before_concept_declarations_legacy, false);
+    } else {
+        writer.write(&settings.templates.break_before_concept_declarations, false);
+    }
+
+    writer.new_line();
+
+    // Preprocessor, BeforeHash was introduced in 9
+    if in_version_range(version, &VERSION::V6_0, &VERSION::V9_0)
+        && settings.preprocessor.indent_pp_directives.get_value() == Some(&"BeforeHash")
+    {
+        writer.write_text("IndentPPDirectives: AfterHash");
+    } else {
+        writer.write(&settings.preprocessor.indent_pp_directives, false);
+    }
+    if settings.preprocessor.indent_pp_directives.get_value() != Some(&"None") {
+        writer.write(&settings.preprocessor.pp_indent_width, false);
+    }
+
+    // AlignEscapedNewlinesLeft was replaced in 5, LeftWithLastLine was introduced in 19
+    if in_version_range(version, &VERSION::V3_5, &VERSION::V5_0) {
+        writer.write(&settings.preprocessor.align_escaped_newlines_left, false);
+    } else if in_version_range(version, &VERSION::V5_0, &VERSION::V19_0)
+        && settings.preprocessor.align_escaped_newlines.get_value() == Some(&"LeftWithLastLine")
+    {
+        writer.write_text("AlignEscapedNewlines: Left");
+    } else {
+        writer.write(&settings.preprocessor.align_escaped_newlines, false);
+    }
 
-        writer.write(&settings.space_before.space_before_square_brackets, true);
-        writer.write(&settings.space_before.space_before_assignment_operators, true);
+    if settings.preprocessor.macro_block_begin.is_set() {
+        writer.write(&settings.preprocessor.macro_block_begin, false);
+        writer.write(&settings.preprocessor.macro_block_end, false);
     }
-}
\ No newline at end of file
+
+    writer.new_line();
+
+    // Includes, SortIncludes was a switch until 13
+    if in_version_range(version, &VERSION::V3_8, &VERSION::V13_0) {
+        writer.write(&settings.includes.sort_includes_legacy, false);
+    } else {
+        writer.write(&settings.includes.sort_includes, false);
+    }
+    writer.write(&settings.includes.include_blocks, false);
+    writer.write_include_categories(&settings.includes.include_categories);
+
+    writer.new_line();
+
+    // Consecutive alignment
+    writer.write_align_consecutive(&settings.align_consecutive.assignments, true);
+    writer.write_align_consecutive(&settings.align_consecutive.declarations, false);
+    writer.write_align_consecutive(&settings.align_consecutive.bit_fields, false);
+    writer.write_align_consecutive(&settings.align_consecutive.macros, false);
+
+    writer.new_line();
+
+    // Comments, AlignTrailingComments became a structure in 16
+    if in_version_range(version, &VERSION::V3_7, &VERSION::V16_0) {
+        writer.write(&settings.comments.align_trailing_comments, false);
+    } else if in_version(version, &VERSION::V16_0) {
+        writer.write_text("AlignTrailingComments:");
+        writer.write(&settings.comments.align_trailing_comments_kind, true);
+        writer.write(&settings.comments.align_trailing_comments_over_empty_lines, true);
+    }
+    writer.write(&settings.comments.spaces_before_trailing_comments, false);
+
+    if in_version(version, &VERSION::V13_0) {
+        writer.write_text("SpacesInLineCommentPrefix:");
+        writer.write(&settings.comments.spaces_in_line_comment_prefix_minimum, true);
+        writer.write(&settings.comments.spaces_in_line_comment_prefix_maximum, true);
+    }
+
+    // ReflowComments became an enum in 20
+    if in_version_range(version, &VERSION::V3_8, &VERSION::V20_0) {
+        writer.write(&settings.comments.reflow_comments, false);
+    } else {
+        writer.write(&settings.comments.reflow_comments_mode, false);
+    }
+
+    writer.new_line();
+
+    // Operators, BreakBeforeBinaryOperators became an enum in 3.8 and AlignOperands in 11
+    if in_version_range(version, &VERSION::V3_7, &VERSION::V3_8) {
+        writer.write(&settings.operators.break_before_binary_operators_legacy, false);
+    } else {
+        writer.write(&settings.operators.break_before_binary_operators, false);
+    }
+    writer.write(&settings.operators.break_before_ternary_operators, false);
+
+    if in_version_range(version, &VERSION::V3_5, &VERSION::V11_0) {
+        writer.write(&settings.operators.align_operands_legacy, false);
+    } else {
+        writer.write(&settings.operators.align_operands, false);
+    }
+    writer.write(&settings.operators.break_binary_operations, false);
+
+    writer.new_line();
+
+    // Braced lists
+    writer.write(&settings.braced_lists.cpp11_braced_list_style, false);
+    writer.write(&settings.braced_lists.align_array_of_structures, false);
+    writer.write(&settings.braced_lists.braced_initializer_indent_width, false);
+
+    writer.new_line();
+
+    // Qualifiers, the order is only used by the custom alignment
+    writer.write(&settings.qualifiers.qualifier_alignment, false);
+    if settings.qualifiers.qualifier_alignment.get_value() == Some(&"Custom") {
+        writer.write(&settings.qualifiers.qualifier_order, false);
+    }
+
+    writer.new_line();
+
+    // Empty lines, KeepEmptyLines replaced the single switches in 19
+    let empty_lines = &settings.empty_lines;
+    if in_version_range(version, &VERSION::V3_7, &VERSION::V19_0) {
+        writer.write(&empty_lines.keep_empty_lines_at_the_start_of_blocks, false);
+        writer.write(&empty_lines.keep_empty_lines_at_eof, false);
+    } else if in_version(version, &VERSION::V19_0) {
+        writer.write_text("KeepEmptyLines:");
+        writer.write(&empty_lines.at_end_of_file, true);
+        writer.write(&empty_lines.at_start_of_block, true);
+        writer.write(&empty_lines.at_start_of_file, true);
+    }
+    writer.write(&empty_lines.separate_definition_blocks, false);
+    writer.write(&empty_lines.insert_newline_at_eof, false);
+    writer.write(&empty_lines.empty_line_before_access_modifier, false);
+
+    writer.new_line();
+
+    // Code changing options, RemoveBracesLLVM is only meant for the LLVM style and contradicts InsertBraces
+    writer.write(&settings.cleanup.insert_braces, false);
+    if settings.cleanup.insert_braces.get_value() != Some(&true) {
+        writer.write(&settings.cleanup.remove_braces_llvm, false);
+    }
+    writer.write(&settings.cleanup.remove_parentheses, false);
+    writer.write(&settings.cleanup.remove_semicolon, false);
+
+    // IntegerLiteralSeparator, the minimum digits followed in 17
+    if in_version(version, &VERSION::V16_0) {
+        let separator = &settings.integer_literal_separator;
+        let min_digits = in_version(version, &VERSION::V17_0);
+        writer.write_text("IntegerLiteralSeparator:");
+        writer.write(&separator.binary, true);
+        if min_digits {
+            writer.write(&separator.binary_min_digits, true);
+        }
+        writer.write(&separator.decimal, true);
+        if min_digits {
+            writer.write(&separator.decimal_min_digits, true);
+        }
+        writer.write(&separator.hex, true);
+        if min_digits {
+            writer.write(&separator.hex_min_digits, true);
+        }
+    }
+
+    writer.new_line();
+
+    // Macros, the lists replace the defaults of clang-format, so they include them
+    writer.write(&settings.macros.for_each_macros, false);
+    writer.write(&settings.macros.if_macros, false);
+    writer.write(&settings.macros.statement_macros, false);
+    writer.write(&settings.macros.statement_attribute_like_macros, false);
+    writer.write(&settings.macros.attribute_macros, false);
+    writer.write(&settings.macros.typename_macros, false);
+    writer.write(&settings.macros.whitespace_sensitive_macros, false);
+
+    writer.new_line();
+
+    // Breaking, the Java annotations are only found in Java sources
+    writer.write(&settings.breaking.break_after_attributes, false);
+    writer.write(&settings.breaking.break_after_java_field_annotations, false);
+    writer.write(&settings.breaking.break_string_literals, false);
+}